@@ -0,0 +1,221 @@
+use advent2022::common::*;
+use std::fmt::Write as _;
+use std::fs::{read_to_string, write};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Settings {
+    pub(crate) runs: usize,
+    pub(crate) warmup: usize,
+    pub(crate) save: Option<String>,
+    pub(crate) compare: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            runs: 10,
+            warmup: 2,
+            save: None,
+            compare: None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Stats {
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+    stddev: Duration,
+}
+
+impl Stats {
+    fn from_samples(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+
+        let secs = sorted.iter().map(|d| d.as_secs_f64()).collect_vec();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Measurement {
+    day: usize,
+    phase: String,
+    stats: Stats,
+}
+
+/// Calls `fun` `warmup` times without measuring, then `runs` times while recording
+/// the duration of every phase it reports.
+pub(crate) fn measure<F>(settings: &Settings, day: usize, mut fun: F) -> Result<Vec<Measurement>>
+where
+    F: FnMut() -> Result<Vec<(&'static str, Duration)>>,
+{
+    if settings.runs == 0 {
+        bail!("number of runs must be at least one");
+    }
+
+    for _ in 0..settings.warmup {
+        fun()?;
+    }
+
+    let mut phases = Vec::<(&str, Vec<Duration>)>::new();
+
+    for _ in 0..settings.runs {
+        for (phase, duration) in fun()? {
+            match phases.iter_mut().find(|(p, _)| *p == phase) {
+                Some((_, samples)) => samples.push(duration),
+                None => phases.push((phase, vec![duration])),
+            }
+        }
+    }
+
+    Ok(phases
+        .into_iter()
+        .map(|(phase, samples)| Measurement {
+            day,
+            phase: phase.to_string(),
+            stats: Stats::from_samples(&samples),
+        })
+        .collect())
+}
+
+pub(crate) fn save(path: &str, measurements: &[Measurement]) -> Result {
+    let mut output = String::from("# day phase min_ns median_ns mean_ns stddev_ns\n");
+
+    for m in measurements {
+        let s = &m.stats;
+        writeln!(
+            output,
+            "{} {} {} {} {} {}",
+            m.day,
+            m.phase,
+            s.min.as_nanos(),
+            s.median.as_nanos(),
+            s.mean.as_nanos(),
+            s.stddev.as_nanos()
+        )?;
+    }
+
+    write(path, output).with_context(|| format!("failed to write: {}", path))
+}
+
+fn parse_measurement(line: &str) -> Result<Measurement> {
+    let fields = line.split_whitespace().collect_vec();
+    let [day, phase, min, median, mean, stddev] = fields[..] else {
+        bail!("expected 6 fields, found {}", fields.len());
+    };
+
+    let nanos = |s: &str| -> Result<Duration> { Ok(Duration::from_nanos(s.parse()?)) };
+
+    Ok(Measurement {
+        day: day.parse()?,
+        phase: phase.to_string(),
+        stats: Stats {
+            min: nanos(min)?,
+            median: nanos(median)?,
+            mean: nanos(mean)?,
+            stddev: nanos(stddev)?,
+        },
+    })
+}
+
+pub(crate) fn load(path: &str) -> Result<Vec<Measurement>> {
+    let content = read_to_string(path).with_context(|| format!("failed to open: {}", path))?;
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            parse_measurement(line).with_context(|| format!("{}:{}: {:?}", path, index + 1, line))
+        })
+        .collect()
+}
+
+fn format_duration(d: Duration) -> String {
+    format!("{:.3} ms", d.as_secs_f64() * 1000.0)
+}
+
+pub(crate) fn print_measurements(measurements: &[Measurement], baseline: &[Measurement]) {
+    println!(
+        "{:>3} | {:<6} | {:>12} | {:>12} | {:>12} | {:>12} | change",
+        "day", "phase", "min", "median", "mean", "stddev"
+    );
+    println!(
+        "{:->3}-+-{:-<6}-+-{:->12}-+-{:->12}-+-{:->12}-+-{:->12}-+-{:-<6}",
+        "", "", "", "", "", "", ""
+    );
+
+    for m in measurements {
+        let previous = baseline
+            .iter()
+            .find(|b| b.day == m.day && b.phase == m.phase);
+
+        let change = match previous {
+            Some(b) if !b.stats.median.is_zero() => {
+                let old = b.stats.median.as_secs_f64();
+                let new = m.stats.median.as_secs_f64();
+                format!("{:+.1}%", (new - old) / old * 100.0)
+            }
+            _ => "-".to_string(),
+        };
+
+        println!(
+            "{:>3} | {:<6} | {:>12} | {:>12} | {:>12} | {:>12} | {}",
+            m.day,
+            m.phase,
+            format_duration(m.stats.min),
+            format_duration(m.stats.median),
+            format_duration(m.stats.mean),
+            format_duration(m.stats.stddev),
+            change
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let m = Measurement {
+            day: 15,
+            phase: "total".to_string(),
+            stats: Stats::from_samples(&[Duration::from_nanos(1234)]),
+        };
+
+        let line = "15 total 1234 1234 1234 0";
+        assert_eq!(parse_measurement(line).unwrap(), m);
+        assert!(parse_measurement("15 total 1234").is_err());
+    }
+}
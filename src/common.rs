@@ -1,14 +1,40 @@
+pub use ::regex::Regex;
+#[allow(unused_imports)]
 pub use anyhow::{anyhow, bail, ensure, Context as _, Error};
+#[allow(unused_imports)]
 pub use itertools::{all, any, enumerate, max, min, rev, Itertools};
-use lazy_static::lazy_static;
-use regex::Regex;
 use std::cmp::{Ord, Ordering};
 use std::default::Default;
-use std::fmt::Display;
+use std::fmt::{self, Display};
 use std::iter::{Flatten, Map, Sum};
+#[allow(unused_imports)]
 pub use std::mem::swap;
 use std::str::FromStr;
-use std::sync::Mutex;
+use std::sync::OnceLock;
+
+mod cycle;
+mod extract;
+mod grid;
+mod interval;
+mod parse;
+mod point;
+mod search;
+mod sparse;
+#[cfg(test)]
+mod testing;
+mod verify;
+pub use crate::regex;
+pub use cycle::{brent, extrapolate, find_cycle, floyd, Cycle};
+pub use extract::{extract_named, extract_regex, FromCaptures, Group};
+pub use grid::{grid_delta, Grid};
+pub use interval::IntervalSet;
+pub use parse::{at_line, error_at, parse_chars, parse_lines, shift_lines, ParseError};
+pub use point::{Dir4, Dir8, Point2, Point3};
+pub use search::{astar, bfs, dijkstra, Search};
+pub use sparse::SparseGrid;
+#[cfg(test)]
+pub(crate) use testing::check_input;
+pub use verify::{Expected, Verdict};
 
 pub type HashMap<K, V> = std::collections::HashMap<K, V, fnv::FnvBuildHasher>;
 pub type HashSet<K> = std::collections::HashSet<K, fnv::FnvBuildHasher>;
@@ -76,33 +102,145 @@ where
         .collect()
 }
 
-lazy_static! {
-    static ref PATTERN_CACHE: Mutex<HashMap<String, &'static Regex>> = Mutex::default();
+/// Compiles a literal regex once and caches it in a static of its own, so
+/// that matching in a loop takes no lock. Panics on the first use of an
+/// invalid pattern.
+#[macro_export]
+macro_rules! regex {
+    ($pattern:literal) => {{
+        static REGEX: std::sync::OnceLock<$crate::common::Regex> = std::sync::OnceLock::new();
+        $crate::common::compile_regex(&REGEX, $pattern)
+    }};
+}
+
+#[doc(hidden)]
+pub fn compile_regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| {
+        Regex::new(pattern).unwrap_or_else(|e| panic!("invalid regex {:?}: {}", pattern, e))
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Answer {
+    #[default]
+    None,
+    Int(i64),
+    Text(String),
+    Image(String),
 }
 
-fn compile(pattern: &str) -> &'static Regex {
-    let mut guard = PATTERN_CACHE.lock().unwrap();
-    if let Some(p) = guard.get(pattern) {
-        return p;
+impl Answer {
+    pub fn image<I, S>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Display,
+    {
+        Answer::Image(rows.into_iter().join("\n"))
     }
+}
 
-    let result = Box::leak(Box::new(Regex::new(pattern).unwrap()));
-    guard.insert(pattern.to_string(), result);
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::None => f.write_str("-"),
+            Answer::Int(v) => write!(f, "{}", v),
+            Answer::Text(s) => f.write_str(s),
+            Answer::Image(img) => f.write_str(img),
+        }
+    }
+}
 
-    result
+macro_rules! impl_answer_from_int {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(v: $ty) -> Self {
+                    Answer::Int(v.try_into().expect("answer does not fit in i64"))
+                }
+            }
+        )*
+    };
 }
 
-#[allow(dead_code)]
-pub fn is_match(pattern: &str, string: &str) -> bool {
-    compile(pattern).is_match(string)
+impl_answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(v: String) -> Self {
+        Answer::Text(v)
+    }
 }
 
-#[allow(dead_code)]
-pub fn find_regex<'t>(pattern: &str, string: &'t str) -> Option<regex::Captures<'t>> {
-    compile(pattern).captures(string)
+impl From<&str> for Answer {
+    fn from(v: &str) -> Self {
+        Answer::Text(v.to_string())
+    }
 }
 
-#[allow(dead_code)]
-pub fn find_regex_all<'t>(pattern: &str, string: &'t str) -> regex::CaptureMatches<'static, 't> {
-    compile(pattern).captures_iter(string)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Solution {
+    pub part_a: Answer,
+    pub part_b: Answer,
+}
+
+impl Solution {
+    pub fn new(part_a: impl Into<Answer>, part_b: impl Into<Answer>) -> Self {
+        Self {
+            part_a: part_a.into(),
+            part_b: part_b.into(),
+        }
+    }
+}
+
+pub trait Puzzle {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part_a(input: &Self::Input) -> Result<Answer>;
+
+    fn part_b(_input: &Self::Input) -> Result<Answer> {
+        Ok(Answer::None)
+    }
+}
+
+/// Splits the input into lines, keeping any trailing whitespace on each line
+/// but dropping the blank lines at the end of the input.
+pub fn lines(input: &str) -> Vec<&str> {
+    let mut lines = input.lines().collect_vec();
+
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines("a\nb\n"), ["a", "b"]);
+        assert_eq!(lines("a\r\nb\r\n"), ["a", "b"]);
+        assert_eq!(lines("  a  \n\nb \n\n  \n"), ["  a  ", "", "b "]);
+        assert!(lines("").is_empty());
+    }
+
+    #[test]
+    fn test_regex() {
+        let first = regex!("^[a-z]+$") as *const Regex;
+        let second = regex!("^[a-z]+$") as *const Regex;
+        assert_ne!(first, second);
+
+        let re = || regex!("^([0-9]+)$");
+        assert!(std::ptr::eq(re(), re()));
+        assert!(re().is_match("123"));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid regex \"[a-z\"")]
+    fn test_invalid_regex() {
+        regex!("[a-z");
+    }
 }
@@ -0,0 +1,193 @@
+use super::*;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+
+/// Sequence of states that, after the first `start` steps, repeats every
+/// `length` steps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step that has the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare. Needs two copies of the state but no history.
+pub fn floyd<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut slow = step(&initial);
+    let mut fast = step(&slow);
+
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        fast = step(&fast);
+    }
+
+    let mut start = 0;
+    slow = initial;
+
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        start += 1;
+    }
+
+    let mut length = 1;
+    fast = step(&slow);
+
+    while slow != fast {
+        fast = step(&fast);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Brent's algorithm, which usually takes fewer steps than Floyd's.
+pub fn brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut slow = initial.clone();
+    let mut fast = step(&initial);
+
+    while slow != fast {
+        if power == length {
+            slow = fast.clone();
+            power *= 2;
+            length = 0;
+        }
+
+        fast = step(&fast);
+        length += 1;
+    }
+
+    slow = initial.clone();
+    fast = initial;
+
+    for _ in 0..length {
+        fast = step(&fast);
+    }
+
+    let mut start = 0;
+
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Runs `step` until the key of a state repeats. Also returns the `value` of
+/// every state up to and including the repeated one.
+pub fn find_cycle<S, K, V, F, G, H>(
+    mut state: S,
+    mut step: F,
+    mut key: G,
+    mut value: H,
+) -> (Cycle, Vec<V>)
+where
+    K: Hash + Eq,
+    F: FnMut(&mut S),
+    G: FnMut(&S) -> K,
+    H: FnMut(&S) -> V,
+{
+    let mut seen = HashMap::default();
+    let mut values = vec![];
+
+    loop {
+        let index = values.len();
+        values.push(value(&state));
+
+        match seen.entry(key(&state)) {
+            Entry::Occupied(e) => {
+                let start = *e.get();
+                let length = index - start;
+
+                return (Cycle { start, length }, values);
+            }
+            Entry::Vacant(e) => {
+                e.insert(index);
+            }
+        }
+
+        step(&mut state);
+    }
+}
+
+/// Value of the state after `steps` steps, assuming the value grows by the
+/// same amount in each repetition of the cycle.
+pub fn extrapolate<S, K, F, G, H>(state: S, steps: usize, step: F, key: G, value: H) -> i64
+where
+    K: Hash + Eq,
+    F: FnMut(&mut S),
+    G: FnMut(&S) -> K,
+    H: FnMut(&S) -> i64,
+{
+    let (cycle, values) = find_cycle(state, step, key, value);
+
+    if steps < values.len() {
+        return values[steps];
+    }
+
+    let growth = values[cycle.start + cycle.length] - values[cycle.start];
+    let repeats = ((steps - cycle.start) / cycle.length) as i64;
+
+    values[cycle.reduce(steps)] + repeats * growth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        // 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+        let step = |&x: &u32| (x * x + 1) % 255;
+        let expected = Cycle {
+            start: 2,
+            length: 6,
+        };
+
+        assert_eq!(floyd(3, step), expected);
+        assert_eq!(brent(3, step), expected);
+
+        let (cycle, values) = find_cycle(3, |x| *x = step(x), |&x| x, |&x| x);
+        assert_eq!(cycle, expected);
+        assert_eq!(values.len(), 9);
+        assert_eq!(values[cycle.reduce(100)], values[cycle.reduce(100 - 6)]);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // The phase cycles through 0..5, gaining 10 per lap.
+        let step = |(phase, total): &mut (u32, i64)| {
+            *phase = (*phase + 1) % 5;
+            *total += if *phase == 0 { 6 } else { 1 };
+        };
+
+        let value = extrapolate((0, 0), 1000, step, |&(p, _)| p, |&(_, t)| t);
+        assert_eq!(value, 1000 / 5 * 10);
+
+        let value = extrapolate((0, 0), 3, step, |&(p, _)| p, |&(_, t)| t);
+        assert_eq!(value, 3);
+    }
+}
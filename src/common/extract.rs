@@ -0,0 +1,129 @@
+use super::*;
+use regex::Captures;
+
+/// Capture group of a regex, referred to by number or by name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Group<'a> {
+    Index(usize),
+    Name(&'a str),
+}
+
+impl Display for Group<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Group::Index(index) => write!(f, "group {}", index),
+            Group::Name(name) => write!(f, "group {:?}", name),
+        }
+    }
+}
+
+/// Values that can be built from the capture groups of a regex match.
+/// Implemented for tuples of `FromStr` types, taking one group per element
+/// in order.
+pub trait FromCaptures: Sized {
+    /// Number of groups the value is built from.
+    const LEN: usize;
+
+    fn from_captures(captures: &Captures, text: &str, groups: &[Group]) -> Result<Self>;
+}
+
+fn parse_group<T: FromStr>(captures: &Captures, text: &str, group: Group) -> Result<T>
+where
+    T::Err: Display,
+{
+    let m = match group {
+        Group::Index(index) => captures.get(index),
+        Group::Name(name) => captures.name(name),
+    };
+    let m = m.ok_or_else(|| anyhow!("{} did not match", group))?;
+
+    m.as_str().parse().map_err(|e| {
+        let message = format!("invalid value {:?}: {}", m.as_str(), e);
+        error_at(text, text[..m.start()].chars().count(), message)
+    })
+}
+
+macro_rules! impl_from_captures {
+    ($($ty:ident $index:literal),*) => {
+        impl<$($ty: FromStr),*> FromCaptures for ($($ty,)*)
+        where
+            $($ty::Err: Display),*
+        {
+            const LEN: usize = [$($index),*].len();
+
+            fn from_captures(captures: &Captures, text: &str, groups: &[Group]) -> Result<Self> {
+                ensure!(
+                    groups.len() == Self::LEN,
+                    "expected {} groups, got {}",
+                    Self::LEN,
+                    groups.len()
+                );
+
+                Ok(($(parse_group::<$ty>(captures, text, groups[$index])?,)*))
+            }
+        }
+    };
+}
+
+impl_from_captures!(A 0);
+impl_from_captures!(A 0, B 1);
+impl_from_captures!(A 0, B 1, C 2);
+impl_from_captures!(A 0, B 1, C 2, D 3);
+impl_from_captures!(A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+fn captures<'t>(pattern: &Regex, string: &'t str) -> Result<Captures<'t>> {
+    pattern
+        .captures(string)
+        .ok_or_else(|| anyhow!("expected a match for {:?}", pattern.as_str()))
+}
+
+/// Matches `pattern` against `string` and converts groups `1..=N` into `T`.
+/// Named groups count as numbered groups as well.
+pub fn extract_regex<T: FromCaptures>(pattern: &Regex, string: &str) -> Result<T> {
+    let groups = (1..=T::LEN).map(Group::Index).collect_vec();
+    T::from_captures(&captures(pattern, string)?, string, &groups)
+}
+
+/// Matches `pattern` against `string` and converts the groups called
+/// `names` into `T`, in the order given.
+pub fn extract_named<T: FromCaptures>(pattern: &Regex, string: &str, names: &[&str]) -> Result<T> {
+    let groups = names.iter().map(|&name| Group::Name(name)).collect_vec();
+    T::from_captures(&captures(pattern, string)?, string, &groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract() {
+        let pattern = regex!("move ([0-9]+) from (?P<src>[0-9]+) to ([a-z]+)");
+        let line = "move 3 from 12 to abc";
+        let (n, src, dst): (usize, i32, String) = extract_regex(pattern, line).unwrap();
+        assert_eq!((n, src, dst.as_str()), (3, 12, "abc"));
+
+        let err = extract_regex::<(u8,)>(regex!("x=([0-9]+)"), "at x=300").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (6, "at x=300"));
+
+        assert!(extract_regex::<(i32,)>(regex!("x=([0-9]+)"), "y=1").is_err());
+        assert!(extract_regex::<(i32, i32)>(regex!("x=([0-9]+)"), "x=1").is_err());
+    }
+
+    #[test]
+    fn test_extract_named() {
+        let pattern = regex!("(?P<dst>[a-z]+) <- (?P<src>[0-9]+)(?: x(?P<n>[0-9]+))?");
+        let (src, dst): (i32, String) = extract_named(pattern, "ab <- 7", &["src", "dst"]).unwrap();
+        assert_eq!((src, dst.as_str()), (7, "ab"));
+
+        let err = extract_named::<(i32,)>(pattern, "ab <- 7", &["n"]).unwrap_err();
+        assert_eq!(err.to_string(), "group \"n\" did not match");
+
+        let err = extract_named::<(u8,)>(pattern, "ab <- 7 x300", &["n"]).unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(err.column, 10);
+
+        assert!(extract_named::<(i32, i32)>(pattern, "ab <- 7", &["src"]).is_err());
+    }
+}
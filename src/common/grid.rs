@@ -0,0 +1,364 @@
+use super::*;
+use std::iter::successors;
+use std::ops::{Index, IndexMut};
+
+/// `[row, col]` offset of a single step in direction `dir`.
+pub fn grid_delta(dir: impl Into<Dir8>) -> [isize; 2] {
+    let d = dir.into().delta::<isize>();
+    [d.y, d.x]
+}
+
+/// Dense two-dimensional grid stored in row-major order. Positions are
+/// `[row, col]` pairs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            rows,
+            cols,
+            data: vec![value; rows * cols],
+        }
+    }
+
+    pub fn from_fn<F>(rows: usize, cols: usize, mut fun: F) -> Self
+    where
+        F: FnMut([usize; 2]) -> T,
+    {
+        let data = (0..rows)
+            .cartesian_product(0..cols)
+            .map(|(i, j)| fun([i, j]))
+            .collect();
+
+        Self { rows, cols, data }
+    }
+
+    /// Parses one cell per character. Lines shorter than the longest line
+    /// are padded with spaces.
+    pub fn parse<F>(lines: &[&str], mut fun: F) -> Result<Self>
+    where
+        F: FnMut(char) -> Result<T>,
+    {
+        let rows = lines.len();
+        let cols = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut data = Vec::with_capacity(rows * cols);
+
+        for (i, &line) in enumerate(lines) {
+            let padded = format!("{:<width$}", line, width = cols);
+            data.extend(at_line(i, line, parse_chars(&padded, &mut fun))?);
+        }
+
+        Ok(Self { rows, cols, data })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn shape(&self) -> [usize; 2] {
+        [self.rows, self.cols]
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn contains(&self, [i, j]: [usize; 2]) -> bool {
+        i < self.rows && j < self.cols
+    }
+
+    pub fn get(&self, pos: [usize; 2]) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.data[pos[0] * self.cols + pos[1]])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: [usize; 2]) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.data[pos[0] * self.cols + pos[1]])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `pos` by `delta`, returning `None` if it leaves the grid.
+    pub fn offset(&self, [i, j]: [usize; 2], [di, dj]: [isize; 2]) -> Option<[usize; 2]> {
+        let pos = [i.checked_add_signed(di)?, j.checked_add_signed(dj)?];
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Moves `pos` one step in direction `dir`, returning `None` if it
+    /// leaves the grid.
+    pub fn step(&self, pos: [usize; 2], dir: impl Into<Dir8>) -> Option<[usize; 2]> {
+        self.offset(pos, grid_delta(dir))
+    }
+
+    pub fn neighbors4(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + '_ {
+        Dir4::ALL.into_iter().filter_map(move |d| self.step(pos, d))
+    }
+
+    pub fn neighbors8(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + '_ {
+        Dir8::ALL.into_iter().filter_map(move |d| self.step(pos, d))
+    }
+
+    /// Positions from `start` (inclusive) stepping in direction `dir` until
+    /// the edge.
+    pub fn line(
+        &self,
+        start: [usize; 2],
+        dir: impl Into<Dir8>,
+    ) -> impl Iterator<Item = [usize; 2]> + '_ {
+        let delta = grid_delta(dir);
+        let start = Some(start).filter(|&p| self.contains(p));
+        successors(start, move |&p| self.offset(p, delta))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = [usize; 2]> {
+        let cols = self.cols;
+        (0..self.len()).map(move |k| [k / cols, k % cols])
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.data.iter_mut()
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ([usize; 2], &T)> {
+        self.positions().zip(&self.data)
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn col(&self, j: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(j < self.cols, "column {} out of bounds", j);
+        self.data[j..].iter().step_by(self.cols)
+    }
+
+    /// Cells where `col - row == offset`, from top-left to bottom-right.
+    pub fn diagonal(&self, offset: isize) -> impl Iterator<Item = &T> {
+        let start = if offset >= 0 {
+            [0, offset as usize]
+        } else {
+            [offset.unsigned_abs(), 0]
+        };
+
+        self.line(start, Dir8::SE).map(|p| &self[p])
+    }
+
+    /// Cells where `row + col == sum`, from top-right to bottom-left.
+    pub fn anti_diagonal(&self, sum: usize) -> impl Iterator<Item = &T> {
+        let start = if sum < self.cols {
+            [0, sum]
+        } else {
+            [sum + 1 - self.cols, self.cols.saturating_sub(1)]
+        };
+
+        self.line(start, Dir8::SW).map(|p| &self[p])
+    }
+
+    pub fn map<U, F>(&self, mut fun: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(&mut fun).collect(),
+        }
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.cols, self.rows, |[i, j]| self[[j, i]].clone())
+    }
+
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.cols, self.rows, |[i, j]| {
+            self[[self.rows - 1 - j, i]].clone()
+        })
+    }
+
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.cols, self.rows, |[i, j]| {
+            self[[j, self.cols - 1 - i]].clone()
+        })
+    }
+
+    pub fn render<F>(&self, mut fun: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        (0..self.rows)
+            .map(|i| self.row(i).iter().map(&mut fun).collect::<String>())
+            .join("\n")
+    }
+
+    /// Binary PPM image of the grid, drawing every cell as a square of
+    /// `scale` by `scale` pixels.
+    pub fn to_ppm<F>(&self, scale: usize, mut color: F) -> Vec<u8>
+    where
+        F: FnMut(&T) -> [u8; 3],
+    {
+        let header = format!("P6\n{} {}\n255\n", self.cols * scale, self.rows * scale);
+        let mut output = header.into_bytes();
+
+        for i in 0..self.rows {
+            let row = self.row(i).iter().map(&mut color).collect_vec();
+
+            for _ in 0..scale {
+                for pixel in &row {
+                    for _ in 0..scale {
+                        output.extend_from_slice(pixel);
+                    }
+                }
+            }
+        }
+
+        output
+    }
+}
+
+impl<T> Index<[usize; 2]> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: [usize; 2]) -> &T {
+        match self.get(pos) {
+            Some(v) => v,
+            None => panic!(
+                "position {:?} out of bounds for grid {:?}",
+                pos,
+                self.shape()
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<[usize; 2]> for Grid<T> {
+    fn index_mut(&mut self, pos: [usize; 2]) -> &mut T {
+        let shape = self.shape();
+
+        match self.get_mut(pos) {
+            Some(v) => v,
+            None => panic!("position {:?} out of bounds for grid {:?}", pos, shape),
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 0..self.rows {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in self.row(i) {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse(&["abc", "de", "ghi"], Ok).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = example();
+        assert_eq!(grid.shape(), [3, 3]);
+        assert_eq!(grid[[1, 2]], ' ');
+        assert_eq!(grid.to_string(), "abc\nde \nghi");
+
+        let err = Grid::<u32>::parse(&["12", "3x"], |c| c.to_digit(10).context("not a digit"));
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .starts_with("line 2, column 2:"));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = example();
+        assert_eq!(grid.neighbors4([0, 0]).collect_vec(), [[0, 1], [1, 0]]);
+        assert_eq!(grid.neighbors8([1, 1]).count(), 8);
+        assert_eq!(grid.offset([0, 0], [-1, 0]), None);
+        assert_eq!(grid.step([0, 0], Dir4::N), None);
+        assert_eq!(grid.step([0, 0], Dir8::SE), Some([1, 1]));
+        assert_eq!(grid_delta(Dir4::W), [0, -1]);
+        assert_eq!(grid.get([3, 0]), None);
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = example();
+        assert_eq!(grid.row(2), ['g', 'h', 'i']);
+        assert_eq!(grid.col(1).collect::<String>(), "beh");
+        assert_eq!(grid.diagonal(0).collect::<String>(), "aei");
+        assert_eq!(grid.diagonal(-1).collect::<String>(), "dh");
+        assert_eq!(grid.anti_diagonal(2).collect::<String>(), "ceg");
+        assert_eq!(grid.anti_diagonal(3).collect::<String>(), " h");
+        assert_eq!(grid.line([0, 1], Dir4::S).count(), 3);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let grid = Grid::parse(&["ab", "cd", "ef"], Ok).unwrap();
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf");
+        assert_eq!(grid.rotate_right().to_string(), "eca\nfdb");
+        assert_eq!(grid.rotate_left().to_string(), "bdf\nace");
+        assert_eq!(grid.rotate_left().rotate_right(), grid);
+        assert_eq!(grid.render(|c| c.to_ascii_uppercase()), "AB\nCD\nEF");
+    }
+
+    #[test]
+    fn test_ppm() {
+        let grid = Grid::parse(&["#."], Ok).unwrap();
+        let image = grid.to_ppm(2, |&c| if c == '#' { [255, 0, 0] } else { [0; 3] });
+
+        let (header, pixels) = image.split_at(11);
+        assert_eq!(header, b"P6\n4 2\n255\n");
+        assert_eq!(pixels.len(), 4 * 2 * 3);
+        assert_eq!(pixels[..12], [255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,247 @@
+use num::PrimInt;
+use std::ops::RangeInclusive;
+
+/// Set of integers stored as sorted, disjoint inclusive ranges. Ranges that
+/// touch are merged, so `1..=3` and `4..=6` become `1..=6`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    // Inclusive `[start, end]` pairs, with a gap between any two of them.
+    ranges: Vec<[T; 2]>,
+}
+
+fn bounds<T: PrimInt>(range: RangeInclusive<T>) -> Option<[T; 2]> {
+    let (start, end) = range.into_inner();
+    (start <= end).then_some([start, end])
+}
+
+/// Whether a range starting at `start` overlaps or directly follows a range
+/// ending at `end`.
+fn touches<T: PrimInt>(end: T, start: T) -> bool {
+    end.checked_add(&T::one()).is_none_or(|next| start <= next)
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of integers in the set. Overflows if that does not fit in `T`.
+    pub fn len(&self) -> T {
+        self.ranges.iter().fold(T::zero(), |total, &[start, end]| {
+            total + (end - start) + T::one()
+        })
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|&[_, end]| end < value);
+        self.ranges
+            .get(index)
+            .is_some_and(|&[start, _]| start <= value)
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let Some([mut start, mut end]) = bounds(range) else {
+            return;
+        };
+
+        // Every range that overlaps or touches the new one is absorbed.
+        let i = self.ranges.partition_point(|r| !touches(r[1], start));
+        let j = self.ranges.partition_point(|r| touches(end, r[0]));
+
+        if i < j {
+            start = start.min(self.ranges[i][0]);
+            end = end.max(self.ranges[j - 1][1]);
+        }
+
+        self.ranges.splice(i..j, [[start, end]]);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let Some([start, end]) = bounds(range) else {
+            return;
+        };
+
+        let i = self.ranges.partition_point(|r| r[1] < start);
+        let j = self.ranges.partition_point(|r| r[0] <= end);
+
+        if i >= j {
+            return;
+        }
+
+        let mut rest = vec![];
+        if self.ranges[i][0] < start {
+            rest.push([self.ranges[i][0], start - T::one()]);
+        }
+
+        if end < self.ranges[j - 1][1] {
+            rest.push([end + T::one(), self.ranges[j - 1][1]]);
+        }
+
+        self.ranges.splice(i..j, rest);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.insert(range);
+        }
+
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let [a0, a1] = self.ranges[i];
+            let [b0, b1] = other.ranges[j];
+            let [start, end] = [a0.max(b0), a1.min(b1)];
+
+            if start <= end {
+                ranges.push([start, end]);
+            }
+
+            if a1 < b1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Integers within `bounds` that are not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        let mut result = Self::from(bounds);
+        for range in self.iter() {
+            result.remove(range);
+        }
+
+        result
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.intersection(other) == *self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&[start, end]| start..=end)
+    }
+
+    /// Ranges between the first and the last range not covered by the set.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges
+            .windows(2)
+            .map(|w| (w[0][1] + T::one())..=(w[1][0] - T::one()))
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Self {
+            ranges: bounds(range).into_iter().collect(),
+        }
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut pending = iter.into_iter().filter_map(bounds).collect::<Vec<_>>();
+        pending.sort_unstable();
+
+        let mut ranges: Vec<[T; 2]> = Vec::with_capacity(pending.len());
+        for [start, end] in pending {
+            match ranges.last_mut() {
+                Some(last) if touches(last[1], start) => last[1] = last[1].max(end),
+                _ => ranges.push([start, end]),
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = IntervalSet::new();
+        set.insert(1..=3);
+        set.insert(7..=9);
+        set.insert(4..=5);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=5, 7..=9]);
+        assert_eq!(set.len(), 8);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), [6..=6]);
+
+        set.insert(6..=6);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=9]);
+
+        set.remove(3..=4);
+        set.remove(9..=20);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=2, 5..=8]);
+        assert!(set.contains(5) && !set.contains(4) && !set.contains(9));
+
+        set.remove(0..=10);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<i64> = [0..=4, 10..=14, 8..=8].into_iter().collect();
+        let b: IntervalSet<i64> = [3..=11, 13..=20].into_iter().collect();
+
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), [0..=20]);
+        assert_eq!(
+            a.intersection(&b).iter().collect::<Vec<_>>(),
+            [3..=4, 8..=8, 10..=11, 13..=14]
+        );
+        assert_eq!(
+            a.complement(-2..=16).iter().collect::<Vec<_>>(),
+            [-2..=-1, 5..=7, 9..=9, 15..=16]
+        );
+        assert!(IntervalSet::from(11..=12).is_subset(&a));
+        assert!(!IntervalSet::from(7..=8).is_subset(&a));
+    }
+
+    #[test]
+    fn test_extremes() {
+        let full = IntervalSet::from(0..=u32::MAX);
+        assert_eq!(full.iter().collect::<Vec<_>>(), [0..=u32::MAX]);
+        assert!(full.contains(u32::MAX));
+        assert!(full.complement(0..=u32::MAX).is_empty());
+
+        let mut set = IntervalSet::new();
+        set.insert(5..=i64::MAX);
+        set.insert(i64::MIN..=3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [i64::MIN..=3, 5..=i64::MAX]);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), [4..=4]);
+        assert_eq!(
+            set.complement(i64::MIN..=i64::MAX)
+                .iter()
+                .collect::<Vec<_>>(),
+            [4..=4]
+        );
+
+        set.insert(4..=4);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [i64::MIN..=i64::MAX]);
+
+        set.remove(i64::MAX..=i64::MAX);
+        set.remove(i64::MIN..=i64::MIN);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [i64::MIN + 1..=i64::MAX - 1]
+        );
+
+        let set: IntervalSet<u8> = [250..=255, 0..=3, 4..=10].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..=10, 250..=255]);
+        assert_eq!(set.len(), 17);
+    }
+}
@@ -0,0 +1,123 @@
+use super::*;
+use std::error::Error as StdError;
+
+/// Parse failure pointing at a position in the input. Lines and columns
+/// count from one; a line of zero means the line is not known yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}, ", self.line)?;
+        }
+
+        write!(
+            f,
+            "column {}: {} in {:?}",
+            self.column, self.message, self.text
+        )
+    }
+}
+
+impl StdError for ParseError {}
+
+/// Error for the character at index `column` of the line `text`.
+pub fn error_at(text: &str, column: usize, message: impl Display) -> Error {
+    ParseError {
+        line: 0,
+        column: column + 1,
+        text: text.to_string(),
+        message: message.to_string(),
+    }
+    .into()
+}
+
+/// Attaches the line number to an error from parsing line `index` (counting
+/// from zero). Errors that do not carry a column point at the whole line.
+pub fn at_line<T>(index: usize, line: &str, result: Result<T>) -> Result<T> {
+    result.map_err(|err| match err.downcast::<ParseError>() {
+        Ok(e) => ParseError {
+            line: index + 1,
+            ..e
+        }
+        .into(),
+        Err(err) => ParseError {
+            line: index + 1,
+            column: 1,
+            text: line.to_string(),
+            message: format!("{:#}", err),
+        }
+        .into(),
+    })
+}
+
+/// Moves the line numbers of an error from parsing `lines[offset..]` back
+/// to their position in `lines`.
+pub fn shift_lines<T>(offset: usize, result: Result<T>) -> Result<T> {
+    result.map_err(|err| match err.downcast::<ParseError>() {
+        Ok(e) if e.line > 0 => ParseError {
+            line: e.line + offset,
+            ..e
+        }
+        .into(),
+        Ok(e) => e.into(),
+        Err(err) => err,
+    })
+}
+
+/// Parses every line with `fun`, reporting the line number on failure.
+pub fn parse_lines<T, F>(lines: &[&str], mut fun: F) -> Result<Vec<T>>
+where
+    F: FnMut(&str) -> Result<T>,
+{
+    enumerate(lines)
+        .map(|(i, &line)| at_line(i, line, fun(line)))
+        .collect()
+}
+
+/// Parses every character of `line` with `fun`, reporting the column on
+/// failure.
+pub fn parse_chars<T, F>(line: &str, mut fun: F) -> Result<Vec<T>>
+where
+    F: FnMut(char) -> Result<T>,
+{
+    enumerate(line.chars())
+        .map(|(j, c)| fun(c).map_err(|e| error_at(line, j, format!("{:#}: {:?}", e, c))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locations() {
+        let digits = |line: &str| parse_chars(line, |c| c.to_digit(10).context("expected a digit"));
+
+        let err = parse_lines(&["12", "3x4"], digits).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 2: expected a digit: 'x' in \"3x4\""
+        );
+
+        let err = parse_lines(&["1", "", "a"], |l| Ok(l.parse::<i32>()?)).unwrap_err();
+        assert_eq!(
+            err.downcast::<ParseError>().unwrap(),
+            ParseError {
+                line: 2,
+                column: 1,
+                text: "".into(),
+                message: "cannot parse integer from empty string".into(),
+            }
+        );
+
+        let err = shift_lines(10, parse_lines(&["x"], digits)).unwrap_err();
+        assert!(err.to_string().starts_with("line 11, column 1:"));
+    }
+}
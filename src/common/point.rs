@@ -0,0 +1,305 @@
+use super::*;
+use num::Signed;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Point or vector in the plane. Directions follow screen conventions: `y`
+/// grows towards the south.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    pub fn map<U, F: FnMut(T) -> U>(self, mut fun: F) -> Point2<U> {
+        Point2::new(fun(self.x), fun(self.y))
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn map<U, F: FnMut(T) -> U>(self, mut fun: F) -> Point3<U> {
+        Point3::new(fun(self.x), fun(self.y), fun(self.z))
+    }
+}
+
+impl<T: Signed + Copy + Ord> Point2<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    pub fn signum(self) -> Self {
+        self.map(|v| v.signum())
+    }
+
+    /// Quarter turn from north towards west.
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Quarter turn from north towards east.
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        Dir4::ALL.into_iter().map(move |d| self + d.delta())
+    }
+
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        Dir8::ALL.into_iter().map(move |d| self + d.delta())
+    }
+}
+
+impl<T: Signed + Copy + Ord> Point3<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = (self - other).map(|v| v.abs());
+        d.x.max(d.y).max(d.z)
+    }
+
+    pub fn signum(self) -> Self {
+        self.map(|v| v.signum())
+    }
+
+    /// The six points sharing a face with this one.
+    pub fn neighbors6(self) -> impl Iterator<Item = Self> {
+        let (one, zero) = (T::one(), T::zero());
+
+        [
+            Self::new(one, zero, zero),
+            Self::new(-one, zero, zero),
+            Self::new(zero, one, zero),
+            Self::new(zero, -one, zero),
+            Self::new(zero, zero, one),
+            Self::new(zero, zero, -one),
+        ]
+        .into_iter()
+        .map(move |d| self + d)
+    }
+}
+
+macro_rules! impl_point_ops {
+    ($name:ident, $($field:ident),*) => {
+        impl<T: Add<Output = T>> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $name { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $name { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $name { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $name { $($field: -self.$field),* }
+            }
+        }
+
+        impl<T: Add<Output = T> + Copy> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Sub<Output = T> + Copy> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2, x, y);
+impl_point_ops!(Point3, x, y, z);
+
+impl<T> From<[T; 2]> for Point2<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Point2<T>> for [T; 2] {
+    fn from(p: Point2<T>) -> Self {
+        [p.x, p.y]
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    N,
+    E,
+    S,
+    W,
+}
+
+impl Dir4 {
+    pub const ALL: [Self; 4] = [Dir4::N, Dir4::E, Dir4::S, Dir4::W];
+
+    pub fn delta<T: Signed>(self) -> Point2<T> {
+        let (one, zero) = (T::one, T::zero);
+
+        match self {
+            Dir4::N => Point2::new(zero(), -one()),
+            Dir4::E => Point2::new(one(), zero()),
+            Dir4::S => Point2::new(zero(), one()),
+            Dir4::W => Point2::new(-one(), zero()),
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    pub const ALL: [Self; 8] = [
+        Dir8::N,
+        Dir8::NE,
+        Dir8::E,
+        Dir8::SE,
+        Dir8::S,
+        Dir8::SW,
+        Dir8::W,
+        Dir8::NW,
+    ];
+
+    pub fn delta<T: Signed>(self) -> Point2<T> {
+        let (one, zero) = (T::one, T::zero);
+
+        match self {
+            Dir8::N => Point2::new(zero(), -one()),
+            Dir8::NE => Point2::new(one(), -one()),
+            Dir8::E => Point2::new(one(), zero()),
+            Dir8::SE => Point2::new(one(), one()),
+            Dir8::S => Point2::new(zero(), one()),
+            Dir8::SW => Point2::new(-one(), one()),
+            Dir8::W => Point2::new(-one(), zero()),
+            Dir8::NW => Point2::new(-one(), -one()),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point2() {
+        let a = Point2::new(3, -2);
+        let b = Point2::new(-1, 4);
+
+        assert_eq!(a + b, Point2::new(2, 2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(a * 2, Point2::new(6, -4));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!((b - a).signum(), Point2::new(-1, 1));
+        assert_eq!(Dir4::N.delta::<i64>().rotate_right(), Dir4::E.delta());
+        assert_eq!(Dir4::N.delta::<i64>().rotate_left(), Dir4::W.delta());
+        assert_eq!(a.neighbors8().filter(|p| a.chebyshev(*p) == 1).count(), 8);
+    }
+
+    #[test]
+    fn test_point3() {
+        let a = Point3::new(1, 2, 3);
+
+        assert_eq!(a.neighbors6().map(|p| a.manhattan(p)).sum::<i32>(), 6);
+        assert_eq!(a.chebyshev(Point3::new(0, 5, 4)), 3);
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Dir4::N.turn_left(), Dir4::W);
+        assert_eq!(Dir4::W.turn_right(), Dir4::N);
+        assert_eq!(Dir4::E.reverse(), Dir4::W);
+        assert_eq!(Dir8::from(Dir4::S), Dir8::S);
+        assert!(Dir8::SW.is_diagonal());
+    }
+}
@@ -0,0 +1,214 @@
+use super::*;
+use binary_heap_plus::BinaryHeap;
+use num::Zero;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// Outcome of a graph search: the cost of every reached node, the node it
+/// was reached from, and the goal if one was found.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    pub dists: HashMap<N, C>,
+    pub preds: HashMap<N, N>,
+    pub goal: Option<N>,
+}
+
+impl<N, C> Search<N, C>
+where
+    N: Hash + Eq + Clone,
+    C: Copy,
+{
+    fn new() -> Self {
+        Self {
+            dists: HashMap::default(),
+            preds: HashMap::default(),
+            goal: None,
+        }
+    }
+
+    pub fn dist(&self, node: &N) -> Option<C> {
+        self.dists.get(node).copied()
+    }
+
+    pub fn goal_dist(&self) -> Option<C> {
+        self.dist(self.goal.as_ref()?)
+    }
+
+    /// Nodes from a start node up to and including `node`.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        if !self.dists.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(prev) = self.preds.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search where every edge costs one. Stops when a node
+/// satisfying `is_goal` is reached, or when all reachable nodes are visited.
+pub fn bfs<N, S, I, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut is_goal: G,
+) -> Search<N, usize>
+where
+    N: Hash + Eq + Clone,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if search.dists.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        let dist = search.dists[&node];
+
+        for next in successors(&node) {
+            if let Entry::Vacant(e) = search.dists.entry(next.clone()) {
+                e.insert(dist + 1);
+                search.preds.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+pub fn dijkstra<N, C, S, I, G>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    is_goal: G,
+) -> Search<N, C>
+where
+    N: Hash + Eq + Clone,
+    C: Zero + Ord + Copy,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    G: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| C::zero(), is_goal)
+}
+
+/// A* search. The heuristic must never overestimate the remaining cost for
+/// the distance to the goal to be optimal.
+pub fn astar<N, C, S, I, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Search<N, C>
+where
+    N: Hash + Eq + Clone,
+    C: Zero + Ord + Copy,
+    S: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new();
+    let mut heap = BinaryHeap::new_by_key(|e: &(C, C, N)| Reverse(e.0));
+
+    for start in starts {
+        search.dists.insert(start.clone(), C::zero());
+        heap.push((heuristic(&start), C::zero(), start));
+    }
+
+    while let Some((_, cost, node)) = heap.pop() {
+        // Skip entries that were superseded by a cheaper path.
+        if cost > search.dists[&node] {
+            continue;
+        }
+
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, step) in successors(&node) {
+            let new_cost = cost + step;
+
+            if search.dists.get(&next).is_none_or(|&d| new_cost < d) {
+                search.dists.insert(next.clone(), new_cost);
+                search.preds.insert(next.clone(), node.clone());
+                heap.push((new_cost + heuristic(&next), new_cost, next));
+            }
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::parse(&["..#", ".##", "..."], Ok).unwrap();
+        let open = |p: &[usize; 2]| {
+            grid.neighbors4(*p)
+                .filter(|&n| grid[n] == '.')
+                .collect_vec()
+        };
+
+        let search = bfs([[0, 0]], open, |&p| p == [2, 2]);
+        assert_eq!(search.goal_dist(), Some(4));
+        assert_eq!(
+            search.path(&[2, 2]).unwrap(),
+            [[0, 0], [1, 0], [2, 0], [2, 1], [2, 2]]
+        );
+
+        let search = bfs([[0, 0]], open, |_| false);
+        assert_eq!(search.goal, None);
+        assert_eq!(search.dists.len(), 6);
+        assert_eq!(search.path(&[0, 2]), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let edges = [(0, 1, 7), (0, 2, 9), (0, 5, 14), (1, 2, 10), (1, 3, 15)]
+            .into_iter()
+            .chain([(2, 3, 11), (2, 5, 2), (3, 4, 6), (4, 5, 9)])
+            .flat_map(|(a, b, w)| [(a, (b, w)), (b, (a, w))])
+            .into_group_map();
+
+        let search = dijkstra([0], |n| edges[n].clone(), |&n| n == 4);
+        assert_eq!(search.goal_dist(), Some(20));
+        assert_eq!(search.path(&4).unwrap(), [0, 2, 5, 4]);
+    }
+
+    #[test]
+    fn test_astar() {
+        let goal = Point2::new(5, 3);
+        let successors = |p: &Point2<i64>| p.neighbors4().map(|n| (n, 1)).collect_vec();
+
+        let search = astar(
+            [Point2::new(0, 0)],
+            successors,
+            |p| p.manhattan(goal),
+            |&p| p == goal,
+        );
+        assert_eq!(search.goal_dist(), Some(8));
+        assert_eq!(search.path(&goal).unwrap().len(), 9);
+    }
+}
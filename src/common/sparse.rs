@@ -0,0 +1,201 @@
+use super::*;
+
+/// Unbounded two-dimensional grid keyed by points, which may be
+/// negative. The bounding box of all occupied cells is kept up to date.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2<i64>, T>,
+    bounds: Option<[Point2<i64>; 2]>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::default(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: Point2<i64>) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn get(&self, pos: Point2<i64>) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Point2<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn insert(&mut self, pos: Point2<i64>, value: T) -> Option<T> {
+        self.bounds = Some(grow(self.bounds, pos));
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Point2<i64>) -> Option<T> {
+        let old = self.cells.remove(&pos)?;
+
+        // Only a cell on the edge of the bounding box can shrink it.
+        if let Some([min, max]) = self.bounds {
+            if pos.x == min.x || pos.x == max.x || pos.y == min.y || pos.y == max.y {
+                self.bounds = compute_bounds(self.cells.keys());
+            }
+        }
+
+        Some(old)
+    }
+
+    /// Inclusive `[min, max]` corners of the occupied cells.
+    pub fn bounds(&self) -> Option<[Point2<i64>; 2]> {
+        self.bounds
+    }
+
+    /// Width and height of the bounding box.
+    pub fn size(&self) -> Point2<i64> {
+        match self.bounds {
+            Some([min, max]) => max - min + Point2::new(1, 1),
+            None => Point2::default(),
+        }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Point2<i64>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    /// Keeps only the cells inside the inclusive window `[min, max]`.
+    pub fn crop(&self, min: Point2<i64>, max: Point2<i64>) -> Self
+    where
+        T: Clone,
+    {
+        self.iter()
+            .filter(|(p, _)| (min.x..=max.x).contains(&p.x) && (min.y..=max.y).contains(&p.y))
+            .map(|(p, v)| (p, v.clone()))
+            .collect()
+    }
+
+    /// Draws the bounding box with `y` increasing downwards.
+    pub fn render<F>(&self, mut fun: F) -> String
+    where
+        F: FnMut(Option<&T>) -> char,
+    {
+        let Some([min, max]) = self.bounds else {
+            return String::new();
+        };
+
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| fun(self.get(Point2::new(x, y))))
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
+
+fn grow(bounds: Option<[Point2<i64>; 2]>, p: Point2<i64>) -> [Point2<i64>; 2] {
+    match bounds {
+        Some([min, max]) => [
+            Point2::new(min.x.min(p.x), min.y.min(p.y)),
+            Point2::new(max.x.max(p.x), max.y.max(p.y)),
+        ],
+        None => [p, p],
+    }
+}
+
+fn compute_bounds<'a>(
+    positions: impl Iterator<Item = &'a Point2<i64>>,
+) -> Option<[Point2<i64>; 2]> {
+    positions.fold(None, |bounds, &p| Some(grow(bounds, p)))
+}
+
+impl<T: PartialEq> PartialEq for SparseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<T: Eq> Eq for SparseGrid<T> {}
+
+impl<T> Extend<(Point2<i64>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point2<i64>, T)>>(&mut self, iter: I) {
+        for (pos, value) in iter {
+            self.insert(pos, value);
+        }
+    }
+}
+
+impl<T> FromIterator<(Point2<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2<i64>, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point2::new(0, 0), 'a');
+        grid.insert(Point2::new(-2, 3), 'b');
+        grid.insert(Point2::new(1, -1), 'c');
+        assert_eq!(
+            grid.bounds(),
+            Some([Point2::new(-2, -1), Point2::new(1, 3)])
+        );
+        assert_eq!(grid.size(), Point2::new(4, 5));
+
+        grid.remove(Point2::new(-2, 3));
+        assert_eq!(grid.bounds(), Some([Point2::new(0, -1), Point2::new(1, 0)]));
+
+        grid.remove(Point2::new(0, 0));
+        grid.remove(Point2::new(1, -1));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let grid: SparseGrid<_> = [
+            (Point2::new(-1, -1), 'a'),
+            (Point2::new(1, 0), 'b'),
+            (Point2::new(0, 1), 'c'),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(grid.render(|c| *c.unwrap_or(&'.')), "a..\n..b\n.c.");
+        assert_eq!(
+            grid.crop(Point2::new(0, -5), Point2::new(5, 5))
+                .render(|c| *c.unwrap_or(&'.')),
+            ".b\nc."
+        );
+        assert_eq!(
+            grid.crop(Point2::new(0, -5), Point2::new(5, 5)).bounds(),
+            Some([Point2::new(0, 0), Point2::new(1, 1)])
+        );
+    }
+}
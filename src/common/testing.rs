@@ -0,0 +1,30 @@
+use super::*;
+use std::fs::read_to_string;
+
+const INPUTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/inputs");
+
+/// Solves the real input of `day` and compares against `inputs/answers`.
+/// Days without an input file or without recorded answers are skipped.
+pub(crate) fn check_input<P: Puzzle>(day: usize) {
+    let Ok(content) = read_to_string(format!("{}/day{:02}", INPUTS, day)) else {
+        return;
+    };
+
+    let answers = read_to_string(format!("{}/answers", INPUTS)).unwrap_or_default();
+    let expected = Expected::parse(&answers).unwrap();
+    if expected.get(day, 'A').is_none() && expected.get(day, 'B').is_none() {
+        return;
+    }
+
+    let input = P::parse(&content).unwrap();
+
+    if let Some(answer) = expected.get(day, 'A') {
+        let result = P::part_a(&input).unwrap();
+        assert_eq!(result.to_string(), answer, "day {} part A", day);
+    }
+
+    if let Some(answer) = expected.get(day, 'B') {
+        let result = P::part_b(&input).unwrap();
+        assert_eq!(result.to_string(), answer, "day {} part B", day);
+    }
+}
@@ -0,0 +1,114 @@
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Verdict::Pass => "PASS",
+            Verdict::Fail => "FAIL",
+            Verdict::Unknown => "UNKNOWN",
+        })
+    }
+}
+
+/// Known-correct answers, read from a file with one `DD P: answer` entry per line.
+///
+/// Multi-line answers leave the text after the colon empty and list their lines
+/// below, each indented by four spaces. Lines starting with `#` are comments.
+#[derive(Debug, Default)]
+pub struct Expected {
+    answers: HashMap<(usize, char), String>,
+}
+
+const INDENT: &str = "    ";
+
+impl Expected {
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut answers = HashMap::<_, String>::default();
+        let mut last_key = None;
+
+        for (index, line) in enumerate(content.lines()) {
+            if let Some(row) = line.strip_prefix(INDENT) {
+                let key = last_key.with_context(|| {
+                    format!("line {}: indented line without an answer", index + 1)
+                })?;
+
+                let answer = answers.get_mut(&key).unwrap();
+                if !answer.is_empty() {
+                    answer.push('\n');
+                }
+
+                answer.push_str(row);
+                continue;
+            }
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let key = Self::parse_key(line)
+                .with_context(|| format!("line {}: invalid entry {:?}", index + 1, line))?;
+
+            let (_, answer) = line.split_once(':').unwrap();
+            if answers.insert(key, answer.trim().to_string()).is_some() {
+                bail!("line {}: duplicate entry {:?}", index + 1, line);
+            }
+
+            last_key = Some(key);
+        }
+
+        Ok(Self { answers })
+    }
+
+    fn parse_key(line: &str) -> Option<(usize, char)> {
+        let (key, _) = line.split_once(':')?;
+        let (day, part) = key.trim().split_once(' ')?;
+        let day = day.parse().ok()?;
+
+        match part.trim() {
+            "A" => Some((day, 'A')),
+            "B" => Some((day, 'B')),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, day: usize, part: char) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn verify(&self, day: usize, part: char, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer.to_string() => Verdict::Pass,
+            Some(_) => Verdict::Fail,
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "# comment\n01 A: 123\n01 B: abc\n\n10 B:\n    #..\n    .#.\n";
+        let expected = Expected::parse(content).unwrap();
+
+        let image = Answer::image(["#..", ".#."]);
+        assert_eq!(expected.verify(1, 'A', &Answer::Int(123)), Verdict::Pass);
+        assert_eq!(expected.verify(1, 'A', &Answer::Int(124)), Verdict::Fail);
+        assert_eq!(expected.verify(1, 'B', &"abc".into()), Verdict::Pass);
+        assert_eq!(expected.verify(10, 'B', &image), Verdict::Pass);
+        assert_eq!(expected.verify(10, 'A', &Answer::Int(1)), Verdict::Unknown);
+
+        assert!(Expected::parse("01 C: 1").is_err());
+        assert!(Expected::parse("01 A: 1\n01 A: 2").is_err());
+        assert!(Expected::parse("    #..").is_err());
+    }
+}
@@ -1,23 +1,55 @@
 use crate::common::*;
 use std::cmp::Reverse;
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let mut totals = vec![];
-    let mut current = 0;
-
-    for line in lines {
-        if line.is_empty() {
-            totals.push(current);
-            current = 0;
-        } else {
-            current += line.parse::<i32>()?;
-        }
+pub struct Day01;
+
+impl Puzzle for Day01 {
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let calories = parse_lines(&lines(input), |line| match line {
+            "" => Ok(None),
+            _ => Ok(Some(line.parse::<i32>()?)),
+        })?;
+
+        let mut totals = calories
+            .split(|c| c.is_none())
+            .map(|elf| elf.iter().flatten().sum())
+            .collect_vec();
+
+        totals.sort_by_key(|&e| Reverse(e));
+        Ok(totals)
+    }
+
+    fn part_a(totals: &Self::Input) -> Result<Answer> {
+        Ok(totals[0].into())
+    }
+
+    fn part_b(totals: &Self::Input) -> Result<Answer> {
+        Ok(totals[..3].iter().sum::<i32>().into())
     }
+}
 
-    totals.sort_by_key(|&e| Reverse(e));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("part A: {:?}", totals[0]);
-    println!("part B: {:?}", totals[0] + totals[1] + totals[2]);
+    const EXAMPLE: &str = include_str!("../inputs/day01.example");
+
+    #[test]
+    fn test_a() {
+        let input = Day01::parse(EXAMPLE).unwrap();
+        assert_eq!(Day01::part_a(&input).unwrap(), 24000.into());
+    }
 
-    Ok(())
+    #[test]
+    fn test_b() {
+        let input = Day01::parse(EXAMPLE).unwrap();
+        assert_eq!(Day01::part_b(&input).unwrap(), 45000.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day01>(1);
+    }
 }
@@ -1,11 +1,11 @@
 use crate::common::*;
 
-type Item = i32;
-const ROCK: Item = 0;
-const PAPER: Item = 1;
-const SCISSORS: Item = 2;
+pub type Item = i32;
+pub const ROCK: Item = 0;
+pub const PAPER: Item = 1;
+pub const SCISSORS: Item = 2;
 
-fn score(first: Item, second: Item) -> i32 {
+pub fn score(first: Item, second: Item) -> i32 {
     let additional = if second == (first + 1) % 3 {
         6
     } else if first == second {
@@ -17,61 +17,67 @@ fn score(first: Item, second: Item) -> i32 {
     second + 1 + additional
 }
 
-fn should_play(first: Item, goal: Item) -> Item {
+pub fn should_play(first: Item, goal: Item) -> Item {
     (first + goal + 2) % 3
 }
 
-fn parse_line(line: &str) -> Result<(Item, Item)> {
-    let mut chars = line.chars();
-    let a = match chars.next().unwrap_or_default() {
+pub fn parse_line(line: &str) -> Result<(Item, Item)> {
+    let chars = line.chars().collect_vec();
+    ensure!(
+        chars.len() == 3 && chars[1] == ' ',
+        "expected two letters separated by a space"
+    );
+
+    let a = match chars[0] {
         'A' => ROCK,
         'B' => PAPER,
         'C' => SCISSORS,
-        _ => bail!("invalid line"),
+        _ => return Err(error_at(line, 0, "expected A, B or C")),
     };
 
-    chars.next();
-
-    let b = match chars.next().unwrap_or_default() {
+    let b = match chars[2] {
         'X' => ROCK,
         'Y' => PAPER,
         'Z' => SCISSORS,
-        _ => bail!("invalid line"),
+        _ => return Err(error_at(line, 2, "expected X, Y or Z")),
     };
 
     Ok((a, b))
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let pairs = lines
-        .iter()
-        .map(|&e| parse_line(e))
-        .collect::<Result<Vec<_>>>()?;
+pub struct Day02;
+
+impl Puzzle for Day02 {
+    type Input = Vec<(Item, Item)>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(&lines(input), parse_line)
+    }
 
-    let total_score = pairs.iter().map(|&(a, b)| score(a, b)).sum::<i32>();
-    println!("part A: {}", total_score);
+    fn part_a(pairs: &Self::Input) -> Result<Answer> {
+        let total_score = pairs.iter().map(|&(a, b)| score(a, b)).sum::<i32>();
+        Ok(total_score.into())
+    }
 
-    let total_score = pairs
-        .iter()
-        .map(|&(a, b)| score(a, should_play(a, b)))
-        .sum::<i32>();
-    println!("part B: {}", total_score);
+    fn part_b(pairs: &Self::Input) -> Result<Answer> {
+        let total_score = pairs
+            .iter()
+            .map(|&(a, b)| score(a, should_play(a, b)))
+            .sum::<i32>();
 
-    Ok(())
+        Ok(total_score.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day02.example");
+
     #[test]
     fn test_a() {
-        let text = "A Y\nB X\nC Z";
-        let pairs = text
-            .lines()
-            .map(parse_line)
-            .collect::<Result<Vec<_>>>()
-            .unwrap();
+        let pairs = Day02::parse(EXAMPLE).unwrap();
 
         let (a, b) = pairs[0];
         assert_eq!(score(a, b), 8);
@@ -81,5 +87,18 @@ mod tests {
 
         let (a, b) = pairs[2];
         assert_eq!(score(a, b), 6);
+
+        assert_eq!(Day02::part_a(&pairs).unwrap(), 15.into());
+    }
+
+    #[test]
+    fn test_b() {
+        let input = Day02::parse(EXAMPLE).unwrap();
+        assert_eq!(Day02::part_b(&input).unwrap(), 12.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day02>(2);
     }
 }
@@ -1,7 +1,7 @@
 use crate::common::*;
 use std::collections::HashSet;
 
-fn priority(c: char) -> i32 {
+pub fn priority(c: char) -> i32 {
     match c {
         'a'..='z' => 1 + c as i32 - 'a' as i32,
         'A'..='Z' => 27 + c as i32 - 'A' as i32,
@@ -9,7 +9,7 @@ fn priority(c: char) -> i32 {
     }
 }
 
-fn find_common_item(line: &str) -> Result<char> {
+pub fn find_common_item(line: &str) -> Result<char> {
     let mid = line.len() / 2;
     let first = line[..mid].chars().collect::<HashSet<_>>();
     let second = line[mid..].chars().collect::<HashSet<_>>();
@@ -20,7 +20,7 @@ fn find_common_item(line: &str) -> Result<char> {
         .ok_or_else(|| anyhow!("no items in common"))
 }
 
-fn find_common_badge(lines: &[&str]) -> Result<char> {
+pub fn find_common_badge(lines: &[String]) -> Result<char> {
     let mut common = lines[0].chars().collect::<HashSet<_>>();
 
     for line in &lines[1..] {
@@ -33,40 +33,67 @@ fn find_common_badge(lines: &[&str]) -> Result<char> {
         .ok_or_else(|| anyhow!("no items in common"))
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let total = lines
-        .iter()
-        .map(|line| find_common_item(line))
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .map(priority)
-        .sum::<i32>();
+pub struct Day03;
 
-    println!("part A: {}", total);
+impl Puzzle for Day03 {
+    type Input = Vec<String>;
 
-    let total = lines
-        .chunks(3)
-        .map(|chunk| find_common_badge(chunk))
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .map(priority)
-        .sum::<i32>();
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(&lines(input), |line| {
+            parse_chars(line, |c| match c {
+                'a'..='z' | 'A'..='Z' => Ok(c),
+                _ => bail!("expected an item letter"),
+            })?;
+
+            Ok(line.to_string())
+        })
+    }
+
+    fn part_a(lines: &Self::Input) -> Result<Answer> {
+        let total = lines
+            .iter()
+            .map(|line| find_common_item(line))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .map(priority)
+            .sum::<i32>();
+
+        Ok(total.into())
+    }
 
-    println!("part B: {}", total);
-    Ok(())
+    fn part_b(lines: &Self::Input) -> Result<Answer> {
+        let total = lines
+            .chunks(3)
+            .map(find_common_badge)
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .map(priority)
+            .sum::<i32>();
+
+        Ok(total.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day03.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day03::parse(EXAMPLE).unwrap();
+        assert_eq!(Day03::part_a(&input).unwrap(), 157.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day03::parse(EXAMPLE).unwrap();
+        assert_eq!(Day03::part_b(&input).unwrap(), 70.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day03>(3);
     }
 }
@@ -1,37 +1,94 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
-
-#[derive(Debug, Deserialize, PartialEq, Recap)]
-#[recap(regex = r#"(?P<a0>[0-9]+)-(?P<a1>[0-9]+),(?P<b0>[0-9]+)-(?P<b1>[0-9]+)"#)]
-struct Line {
-    a0: i32,
-    a1: i32,
-    b0: i32,
-    b1: i32,
+
+#[derive(Debug, PartialEq)]
+pub struct Line {
+    pub a0: i32,
+    pub a1: i32,
+    pub b0: i32,
+    pub b1: i32,
 }
 
 impl Line {
-    fn is_fully_contained(&self) -> bool {
-        self.a0 >= self.b0 && self.a1 <= self.b1 || self.b0 >= self.a0 && self.b1 <= self.a1
+    pub fn parse(line: &str) -> Result<Self> {
+        let (a0, a1, b0, b1) = extract_regex(regex!("^([0-9]+)-([0-9]+),([0-9]+)-([0-9]+)$"), line)
+            .context("expected two section ranges")?;
+
+        Ok(Self { a0, a1, b0, b1 })
+    }
+
+    pub fn sections(&self) -> [IntervalSet<i32>; 2] {
+        [
+            IntervalSet::from(self.a0..=self.a1),
+            IntervalSet::from(self.b0..=self.b1),
+        ]
+    }
+
+    pub fn is_fully_contained(&self) -> bool {
+        let [a, b] = self.sections();
+        a.is_subset(&b) || b.is_subset(&a)
     }
 
-    fn is_overlapping(&self) -> bool {
-        self.a0 <= self.b1 && self.a1 >= self.b0
+    pub fn is_overlapping(&self) -> bool {
+        let [a, b] = self.sections();
+        !a.intersection(&b).is_empty()
     }
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let lines = lines
-        .iter()
-        .map(|&l| Line::try_from(l))
-        .collect::<Result<Vec<_>, _>>()?;
+pub struct Day04;
 
-    let count = lines.iter().filter(|l| l.is_fully_contained()).count();
-    println!("part A: {}", count);
+impl Puzzle for Day04 {
+    type Input = Vec<Line>;
 
-    let count = lines.iter().filter(|l| l.is_overlapping()).count();
-    println!("part A: {}", count);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(&lines(input), Line::parse)
+    }
+
+    fn part_a(lines: &Self::Input) -> Result<Answer> {
+        Ok(lines
+            .iter()
+            .filter(|l| l.is_fully_contained())
+            .count()
+            .into())
+    }
+
+    fn part_b(lines: &Self::Input) -> Result<Answer> {
+        Ok(lines.iter().filter(|l| l.is_overlapping()).count().into())
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../inputs/day04.example");
+
+    #[test]
+    fn test_a() {
+        let input = Day04::parse(EXAMPLE).unwrap();
+        assert_eq!(Day04::part_a(&input).unwrap(), 2.into());
+    }
+
+    #[test]
+    fn test_b() {
+        let input = Day04::parse(EXAMPLE).unwrap();
+        assert_eq!(Day04::part_b(&input).unwrap(), 4.into());
+    }
+
+    #[test]
+    fn test_parse() {
+        let err = Day04::parse("2-4,6-8\n2-3,x-5").unwrap_err();
+        assert!(err.to_string().starts_with("line 2, column 1:"), "{}", err);
+        assert!(err.to_string().contains("\"2-3,x-5\""), "{}", err);
+
+        let err = Day04::parse("2-3,4-5junk").unwrap_err();
+        assert!(err.to_string().starts_with("line 1, column 1:"), "{}", err);
+
+        let err = Day04::parse("1-2,3-99999999999").unwrap_err();
+        assert!(err.to_string().starts_with("line 1, column 7:"), "{}", err);
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day04>(4);
+    }
 }
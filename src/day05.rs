@@ -1,86 +1,400 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
-use std::collections::VecDeque;
-
-#[derive(Debug, Deserialize, PartialEq, Recap)]
-#[recap(regex = r#"move (?P<count>[0-9]+) from (?P<src>[1-9]) to (?P<dst>[1-9])"#)]
-struct Instruction {
-    count: i32,
-    src: usize,
-    dst: usize,
+
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    pub count: usize,
+    pub src: usize,
+    pub dst: usize,
 }
 
-fn parse_instructions(lines: &[&str]) -> Result<Vec<Instruction>> {
-    lines
-        .iter()
-        .map(|&l| Instruction::try_from(l).context("invalid line"))
-        .collect()
+pub type Stack = Vec<String>;
+
+pub fn parse_instructions(lines: &[&str]) -> Result<Vec<Instruction>> {
+    parse_lines(lines, |line| {
+        let (count, src, dst) =
+            extract_regex(regex!("^move ([0-9]+) from ([0-9]+) to ([0-9]+)$"), line)
+                .context("expected a move instruction")?;
+
+        Ok(Instruction { count, src, dst })
+    })
 }
 
-fn parse_stacks(lines: &[&str], ncols: usize) -> Vec<VecDeque<char>> {
-    let mut queues = vec![VecDeque::new(); ncols];
+/// Parses the drawing of the stacks. The last line numbers the stacks and
+/// every crate belongs to the stack whose number it overlaps.
+pub fn parse_stacks(lines: &[&str]) -> Result<Vec<Stack>> {
+    let (&numbers, rows) = lines
+        .split_last()
+        .context("expected a drawing of the stacks")?;
+    let labels = regex!("[0-9]+").find_iter(numbers).collect_vec();
+    let index = rows.len();
+    ensure!(!labels.is_empty(), "expected a line numbering the stacks");
 
-    for line in lines {
-        for (i, c) in line.char_indices() {
-            if ('A'..='Z').contains(&c) {
-                queues[i / 4].push_front(c);
-            }
+    for (k, label) in enumerate(&labels) {
+        if label.as_str() != (k + 1).to_string() {
+            let err = error_at(numbers, label.start(), format!("expected stack {}", k + 1));
+            return at_line(index, numbers, Err(err));
+        }
+    }
+
+    let mut stacks = vec![Stack::new(); labels.len()];
+
+    for (height, (index, &row)) in enumerate(rows).rev().enumerate() {
+        at_line(index, row, parse_row(row, &labels, height, &mut stacks))?;
+    }
+
+    Ok(stacks)
+}
+
+/// Puts the crates of the drawing row that is `height` rows from the bottom
+/// on their stacks.
+fn parse_row(row: &str, labels: &[regex::Match], height: usize, stacks: &mut [Stack]) -> Result {
+    ensure!(
+        regex!(r"^(\s*\[[^\[\]\s]+\])*\s*$").is_match(row),
+        "expected crates such as [A]"
+    );
+
+    for m in regex!(r"\[([^\[\]\s]+)\]").captures_iter(row) {
+        let span = m.get(0).unwrap().range();
+        let k = labels
+            .iter()
+            .position(|l| l.start() < span.end && span.start < l.end())
+            .ok_or_else(|| error_at(row, span.start, "crate is not above a stack number"))?;
+
+        if stacks[k].len() != height {
+            let message = format!("crate is not on top of stack {}", k + 1);
+            return Err(error_at(row, span.start, message));
         }
+
+        stacks[k].push(m[1].to_string());
     }
 
-    queues
+    Ok(())
 }
 
-fn top_crates(queues: &[VecDeque<char>]) -> String {
-    queues
+pub fn top_crates(stacks: &[Stack]) -> String {
+    stacks.iter().filter_map(|s| s.last()).join("")
+}
+
+/// Draws the stacks the way the puzzle input does, with every column as
+/// wide as the longest crate.
+pub fn render_stacks(stacks: &[Stack]) -> String {
+    let width = stacks
         .iter()
-        .map(|q| q.back().copied().unwrap_or('?'))
-        .collect()
+        .flatten()
+        .map(|c| c.len() + 2)
+        .max()
+        .unwrap_or(3);
+    let height = stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut rows = vec![];
+
+    for level in (0..height).rev() {
+        let row = stacks
+            .iter()
+            .map(|s| match s.get(level) {
+                Some(c) => format!("{:<width$}", format!("[{}]", c)),
+                None => " ".repeat(width),
+            })
+            .join(" ");
+
+        rows.push(row);
+    }
+
+    let numbers = (1..=stacks.len())
+        .map(|k| format!("{:^width$}", k))
+        .join(" ");
+
+    rows.push(numbers);
+    rows.join("\n")
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let queues = parse_stacks(&lines[..8], 9);
-    let instrs = parse_instructions(&lines[10..])?;
+pub trait Crane {
+    /// Most crates the crane can pick up at once.
+    fn capacity(&self) -> usize;
+
+    /// Moves the top `count` crates of `src` onto `dst`, lifting as many at
+    /// a time as the capacity allows.
+    fn lift(&self, src: &mut Stack, dst: &mut Stack, mut count: usize) -> Result {
+        let capacity = self.capacity();
+        ensure!(capacity > 0, "crane cannot lift any crates");
+        ensure!(
+            count <= src.len(),
+            "cannot move {} crates from a stack holding {}",
+            count,
+            src.len()
+        );
 
-    let mut result = queues.clone();
-    for instr in &instrs {
-        for _ in 0..instr.count {
-            let item = result[instr.src - 1].pop_back().unwrap_or('?');
-            result[instr.dst - 1].push_back(item);
+        while count > 0 {
+            let n = count.min(capacity);
+            dst.extend(src.drain(src.len() - n..));
+            count -= n;
         }
+
+        Ok(())
     }
+}
+
+/// Moves crates one at a time.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+/// Moves any number of crates at once.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// Moves at most `capacity` crates at once.
+pub struct LimitedCrane {
+    capacity: usize,
+}
+
+impl LimitedCrane {
+    pub fn new(capacity: usize) -> Result<Self> {
+        ensure!(capacity > 0, "crane capacity must be at least one");
+        Ok(Self { capacity })
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
 
-    println!("part A: {}", top_crates(&result));
+/// Runs the instructions with the given crane. `observe` sees the index of
+/// every instruction and the stacks after it.
+pub fn simulate<C, F>(
+    crane: &C,
+    stacks: &[Stack],
+    instrs: &[Instruction],
+    mut observe: F,
+) -> Result<Vec<Stack>>
+where
+    C: Crane,
+    F: FnMut(usize, &[Stack]),
+{
+    ensure!(crane.capacity() > 0, "crane cannot lift any crates");
+    let mut stacks = stacks.to_vec();
 
-    let mut result = queues.clone();
-    let mut temp = vec![];
-    for instr in &instrs {
-        for _ in 0..instr.count {
-            temp.push(result[instr.src - 1].pop_back().unwrap_or('?'));
+    for (index, instr) in enumerate(instrs) {
+        let &Instruction { count, src, dst } = instr;
+        let stack = |k: usize| {
+            k.checked_sub(1)
+                .filter(|&k| k < stacks.len())
+                .with_context(|| format!("instruction {}: there is no stack {}", index + 1, k))
+        };
+
+        let (i, j) = (stack(src)?, stack(dst)?);
+        let available = stacks[i].len();
+        ensure!(
+            count <= available,
+            "instruction {}: cannot move {} crates from stack {} holding {}",
+            index + 1,
+            count,
+            src,
+            available
+        );
+
+        if i != j {
+            let [src, dst] = stacks.get_disjoint_mut([i, j]).unwrap();
+            crane
+                .lift(src, dst, count)
+                .with_context(|| format!("instruction {}", index + 1))?;
         }
 
-        while let Some(item) = temp.pop() {
-            result[instr.dst - 1].push_back(item);
+        observe(index, &stacks);
+    }
+
+    Ok(stacks)
+}
+
+pub struct Day05;
+
+impl Puzzle for Day05 {
+    type Input = (Vec<Stack>, Vec<Instruction>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let lines = lines(input);
+        let n = lines
+            .iter()
+            .position(|l| l.trim().is_empty())
+            .context("expected a drawing, a blank line and the instructions")?;
+
+        let stacks = parse_stacks(&lines[..n])?;
+        let instrs = shift_lines(n + 1, parse_instructions(&lines[n + 1..]))?;
+
+        for (i, instr) in enumerate(&instrs) {
+            if let Some(k) = [instr.src, instr.dst]
+                .into_iter()
+                .find(|k| !(1..=stacks.len()).contains(k))
+            {
+                let line = lines[n + 1 + i];
+                at_line(n + 1 + i, line, Err(anyhow!("there is no stack {}", k)))?;
+            }
         }
+
+        Ok((stacks, instrs))
     }
 
-    println!("part B: {}", top_crates(&result));
+    fn part_a((stacks, instrs): &Self::Input) -> Result<Answer> {
+        let result = simulate(&CrateMover9000, stacks, instrs, |_, _| {})?;
+        Ok(top_crates(&result).into())
+    }
 
-    Ok(())
+    fn part_b((stacks, instrs): &Self::Input) -> Result<Answer> {
+        let result = simulate(&CrateMover9001, stacks, instrs, |_, _| {})?;
+        Ok(top_crates(&result).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day05.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day05::parse(EXAMPLE).unwrap();
+        assert_eq!(Day05::part_a(&input).unwrap(), "CMZ".into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day05::parse(EXAMPLE).unwrap();
+        assert_eq!(Day05::part_b(&input).unwrap(), "MCD".into());
+    }
+
+    #[test]
+    fn test_drawing() {
+        let input = [
+            "                                    [XY]",
+            "[A]                                 [B] ",
+            " 1   2   3   4   5   6   7   8   9  10 ",
+            "",
+            "move 2 from 10 to 1",
+        ]
+        .join("\n");
+
+        let (stacks, instrs) = Day05::parse(&input).unwrap();
+        assert_eq!(stacks.len(), 10);
+        assert_eq!(stacks[9], ["B", "XY"]);
+        assert_eq!(
+            instrs,
+            [Instruction {
+                count: 2,
+                src: 10,
+                dst: 1
+            }]
+        );
+
+        let input = (stacks, instrs);
+        assert_eq!(Day05::part_a(&input).unwrap(), "B".into());
+        assert_eq!(Day05::part_b(&input).unwrap(), "XY".into());
+
+        let err = Day05::parse("[A]\n    [B]\n 1   2\n\nmove 1 from 1 to 2").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("line 1, column 1: crate is not on top"),
+            "{}",
+            err
+        );
+
+        let err = Day05::parse("[A]\n 1\n\nmove 1 from 1 to 2").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("line 4, column 1: there is no stack 2"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_cranes() {
+        let (stacks, instrs) = Day05::parse(EXAMPLE).unwrap();
+        let drawing = lines(EXAMPLE)[..4].join("\n");
+        assert_eq!(render_stacks(&stacks), drawing);
+
+        let mut drawings = vec![];
+        let result = simulate(&CrateMover9000, &stacks, &instrs, |i, stacks| {
+            drawings.push((i, render_stacks(stacks)));
+        });
+
+        assert_eq!(top_crates(&result.unwrap()), "CMZ");
+        assert_eq!(drawings.len(), 4);
+        assert_eq!(
+            drawings[1],
+            (
+                1,
+                [
+                    "        [Z]",
+                    "        [N]",
+                    "    [C] [D]",
+                    "    [M] [P]",
+                    " 1   2   3 "
+                ]
+                .join("\n")
+            )
+        );
+
+        let crane = LimitedCrane::new(2).unwrap();
+        let result = simulate(&crane, &stacks, &instrs, |_, _| {});
+        assert_eq!(top_crates(&result.unwrap()), "MCZ");
+
+        let bad = [
+            Instruction {
+                count: 1,
+                src: 1,
+                dst: 2,
+            },
+            Instruction {
+                count: 4,
+                src: 1,
+                dst: 3,
+            },
+        ];
+        let err = simulate(&CrateMover9001, &stacks, &bad, |_, _| {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "instruction 2: cannot move 4 crates from stack 1 holding 1"
+        );
+    }
+
+    #[test]
+    fn test_crane_limits() {
+        assert!(LimitedCrane::new(0).is_err());
+
+        struct Broken;
+
+        impl Crane for Broken {
+            fn capacity(&self) -> usize {
+                0
+            }
+        }
+
+        let (stacks, instrs) = Day05::parse(EXAMPLE).unwrap();
+        assert!(simulate(&Broken, &stacks, &instrs, |_, _| {}).is_err());
+
+        let (mut src, mut dst) = (vec!["A".to_string()], vec![]);
+        let err = CrateMover9001.lift(&mut src, &mut dst, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot move 2 crates from a stack holding 1"
+        );
+        assert!(Broken.lift(&mut src, &mut dst, 1).is_err());
+        assert_eq!((src.len(), dst.len()), (1, 0));
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day05>(5);
     }
 }
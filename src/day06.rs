@@ -1,7 +1,7 @@
 use crate::common::*;
 use std::collections::{HashSet, VecDeque};
 
-fn find_marker_position(line: &str, n: usize) -> usize {
+pub fn find_marker_position(line: &str, n: usize) -> usize {
     let mut window = VecDeque::new();
 
     for (index, c) in line.chars().enumerate() {
@@ -23,18 +23,31 @@ fn find_marker_position(line: &str, n: usize) -> usize {
     panic!("no marker found");
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let line = lines[0];
-    println!("part A: {}", find_marker_position(line, 4));
-    println!("part B: {}", find_marker_position(line, 14));
+pub struct Day06;
 
-    Ok(())
+impl Puzzle for Day06 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let line = lines(input).first().copied().context("input is empty")?;
+        Ok(line.to_string())
+    }
+
+    fn part_a(line: &Self::Input) -> Result<Answer> {
+        Ok(find_marker_position(line, 4).into())
+    }
+
+    fn part_b(line: &Self::Input) -> Result<Answer> {
+        Ok(find_marker_position(line, 14).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day06.example");
+
     #[test]
     fn test_a() {
         assert_eq!(find_marker_position("bvwbjplbgvbhsrlpgdmjqwftvncz", 4), 5);
@@ -47,6 +60,9 @@ mod tests {
             find_marker_position("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 4),
             11
         );
+
+        let input = Day06::parse(EXAMPLE).unwrap();
+        assert_eq!(Day06::part_a(&input).unwrap(), 7.into());
     }
 
     #[test]
@@ -65,5 +81,13 @@ mod tests {
             find_marker_position("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14),
             26
         );
+
+        let input = Day06::parse(EXAMPLE).unwrap();
+        assert_eq!(Day06::part_b(&input).unwrap(), 19.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day06>(6);
     }
 }
@@ -1,50 +1,79 @@
 use crate::common::*;
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let mut path = vec![];
-    let mut sizes = HashMap::<_, i32>::default();
-
-    for &line in lines {
-        if line == "$ cd /" {
-            path = vec![];
-        } else if line == "$ cd .." {
-            path.pop();
-        } else if let Some(matches) = find_regex("\\$ cd ([a-z]+)", line) {
-            path.push(matches[1].to_string());
-        } else if let Some(matches) = find_regex("([0-9]+) [a-z.]+", line) {
-            let size = matches[1].parse::<i32>().unwrap();
-
-            for i in 0..=path.len() {
-                *sizes.entry(path[..i].to_vec()).or_default() += size;
+pub struct Day07;
+
+impl Puzzle for Day07 {
+    type Input = HashMap<Vec<String>, i32>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut path = vec![];
+        let mut sizes = HashMap::<_, i32>::default();
+
+        for (index, &line) in enumerate(&lines(input)) {
+            if line == "$ cd /" {
+                path = vec![];
+            } else if line == "$ cd .." {
+                path.pop();
+            } else if let Some(matches) = regex!("\\$ cd ([a-z]+)").captures(line) {
+                path.push(matches[1].to_string());
+            } else if regex!("^[0-9]+ ").is_match(line) {
+                let (size,): (i32,) = at_line(
+                    index,
+                    line,
+                    extract_regex(regex!("^([0-9]+) [a-z.]+$"), line),
+                )?;
+
+                for i in 0..=path.len() {
+                    *sizes.entry(path[..i].to_vec()).or_default() += size;
+                }
+            } else if line != "$ ls" && !line.starts_with("dir ") {
+                at_line(index, line, Err(anyhow!("expected a command or a listing")))?;
             }
         }
+
+        Ok(sizes)
     }
 
-    let a = sizes.values().filter(|&&e| e <= 100000).sum::<i32>();
-    println!("part A: {}", a);
+    fn part_a(sizes: &Self::Input) -> Result<Answer> {
+        Ok(sizes.values().filter(|&&e| e <= 100000).sum::<i32>().into())
+    }
 
-    let capacity = 70000000;
-    let required = 30000000;
-    let used = sizes[&Vec::<String>::new()];
-    let threshold = required + used - capacity;
+    fn part_b(sizes: &Self::Input) -> Result<Answer> {
+        let capacity = 70000000;
+        let required = 30000000;
+        let used = sizes[&Vec::<String>::new()];
+        let threshold = required + used - capacity;
 
-    let b = sizes.values().filter(|&&e| e >= threshold).min();
-    println!("part B: {:?}", b);
+        let b = sizes
+            .values()
+            .filter(|&&e| e >= threshold)
+            .min()
+            .context("no directory is large enough")?;
 
-    Ok(())
+        Ok((*b).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day07.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day07::parse(EXAMPLE).unwrap();
+        assert_eq!(Day07::part_a(&input).unwrap(), 95437.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day07::parse(EXAMPLE).unwrap();
+        assert_eq!(Day07::part_b(&input).unwrap(), 24933642.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day07>(7);
     }
 }
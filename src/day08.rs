@@ -1,107 +1,95 @@
 use crate::common::*;
-use ndarray::{Array2, ArrayView2, ArrayViewMut2, Axis};
 
-fn parse_grid(lines: Lines) -> Array2<i32> {
-    let rows = lines.len();
-    let cols = lines[0].len();
-    let mut grid = Array2::from_elem((rows, cols), 0);
+pub fn parse_grid(lines: Lines) -> Result<Grid<i32>> {
+    Grid::parse(lines, |c| {
+        c.to_digit(10)
+            .map(|d| d as i32)
+            .ok_or_else(|| anyhow!("expected a digit"))
+    })
+}
 
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            grid[[i, j]] = c.to_digit(10).unwrap() as i32;
-        }
-    }
+pub fn is_visible(grid: &Grid<i32>, pos: [usize; 2]) -> bool {
+    let height = grid[pos];
 
-    grid
+    Dir4::ALL
+        .into_iter()
+        .any(|d| grid.line(pos, d).skip(1).all(|p| grid[p] < height))
 }
 
-fn scan_visible(grid: ArrayView2<i32>, mut visible: ArrayViewMut2<bool>) {
-    for i in 0..grid.len_of(Axis(0)) {
-        let mut highest = -1;
+pub fn score_spot(grid: &Grid<i32>, pos: [usize; 2]) -> usize {
+    let height = grid[pos];
 
-        for j in 0..grid.len_of(Axis(1)) {
-            if grid[[i, j]] > highest {
-                visible[[i, j]] = true;
-                highest = grid[[i, j]];
-            }
-        }
+    Dir4::ALL
+        .into_iter()
+        .map(|d| {
+            let mut n = 0;
 
-        let mut highest = -1;
+            for p in grid.line(pos, d).skip(1) {
+                n += 1;
 
-        for j in (0..grid.len_of(Axis(1))).rev() {
-            if grid[[i, j]] > highest {
-                visible[[i, j]] = true;
-                highest = grid[[i, j]];
+                if grid[p] >= height {
+                    break;
+                }
             }
-        }
-    }
+
+            n
+        })
+        .product()
 }
 
-fn score_spot(grid: ArrayView2<i32>, [i, j]: [usize; 2]) -> usize {
-    let (rows, cols) = grid.dim();
-    let height = grid[[i, j]];
+pub struct Day08;
 
-    let (mut a, mut b, mut c, mut d) = (1, 1, 1, 1);
+impl Puzzle for Day08 {
+    type Input = Grid<i32>;
 
-    // down
-    while i + a + 1 < rows && grid[[i + a, j]] < height {
-        a += 1;
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_grid(&lines(input))
     }
 
-    // up
-    while i >= b + 1 && grid[[i - b, j]] < height {
-        b += 1;
+    fn part_a(grid: &Self::Input) -> Result<Answer> {
+        let count = grid.positions().filter(|&p| is_visible(grid, p)).count();
+        Ok(count.into())
     }
 
-    // right
-    while j + c + 1 < cols && grid[[i, j + c]] < height {
-        c += 1;
-    }
+    fn part_b(grid: &Self::Input) -> Result<Answer> {
+        let best = grid
+            .positions()
+            .map(|p| score_spot(grid, p))
+            .max()
+            .unwrap_or_default();
 
-    //left
-    while j >= d + 1 && grid[[i, j - d]] < height {
-        d += 1;
+        Ok(best.into())
     }
-
-    a * b * c * d
-}
-
-pub(crate) fn run(lines: Lines) -> Result {
-    let grid = parse_grid(lines);
-    let mut visible = Array2::from_elem(grid.dim(), false);
-
-    scan_visible(grid.view(), visible.view_mut());
-    scan_visible(
-        grid.view().reversed_axes(),
-        visible.view_mut().reversed_axes(),
-    );
-
-    let n = visible.iter().filter(|&&e| e).count();
-    println!("part A: {}", n);
-
-    let best = grid
-        .indexed_iter()
-        .map(|((i, j), _)| score_spot(grid.view(), [i, j]))
-        .max();
-
-    println!("part B: {:?}", best);
-
-    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day08.example");
+
     #[test]
     fn test_a() {
-        //
+        let grid = parse_grid(&lines(EXAMPLE)).unwrap();
+
+        assert!(is_visible(&grid, [1, 1]));
+        assert!(!is_visible(&grid, [1, 3]));
+        assert_eq!(
+            grid.positions().filter(|&p| is_visible(&grid, p)).count(),
+            21
+        );
     }
 
     #[test]
     fn test_b() {
-        let grid = parse_grid(&["30373", "25512", "65332", "33549", "35390"]);
+        let grid = parse_grid(&lines(EXAMPLE)).unwrap();
+
+        assert_eq!(score_spot(&grid, [1, 2]), 4);
+        assert_eq!(score_spot(&grid, [3, 2]), 8);
+    }
 
-        assert_eq!(score_spot(grid.view(), [1, 2]), 5);
+    #[test]
+    fn test_input() {
+        check_input::<Day08>(8);
     }
 }
@@ -1,83 +1,97 @@
 use crate::common::*;
 use std::collections::HashSet;
 
-type Pos = [i32; 2];
+pub type Pos = Point2<i64>;
 
-fn parse_moves(lines: Lines) -> Vec<Pos> {
-    let [mut x, mut y] = [0, 0];
-    let mut pos = vec![[x, y]];
+pub fn parse_move(line: &str) -> Result<(Dir4, usize)> {
+    let (dir, steps) = extract_regex(regex!("^([RLUD]) ([0-9]+)$"), line)
+        .context("expected a direction and a number of steps")?;
 
-    for line in lines {
-        let matches = find_regex("([RLUD]) ([0-9]+)", line).unwrap();
-        let steps = matches[2].parse().unwrap();
+    let dir = match dir {
+        'R' => Dir4::E,
+        'L' => Dir4::W,
+        'U' => Dir4::N,
+        'D' => Dir4::S,
+        _ => unreachable!(),
+    };
 
+    Ok((dir, steps))
+}
+
+pub fn parse_moves(lines: Lines) -> Result<Vec<Pos>> {
+    let mut head = Pos::default();
+    let mut pos = vec![head];
+
+    for (dir, steps) in parse_lines(lines, parse_move)? {
         for _ in 0..steps {
-            match &matches[1] {
-                "R" => x += 1,
-                "L" => x -= 1,
-                "U" => y += 1,
-                "D" => y -= 1,
-                _ => unreachable!(),
-            }
-
-            pos.push([x, y]);
+            head += dir.delta();
+            pos.push(head);
         }
     }
 
-    pos
+    Ok(pos)
 }
 
-fn follow_path(head: &[Pos]) -> Vec<Pos> {
-    let [mut tx, mut ty] = [0, 0];
-    let mut tail = vec![[tx, ty]];
-
-    for &[hx, hy] in head {
-        if (tx - hx).abs() > 1 || (ty - hy).abs() > 1 {
-            if tx < hx {
-                tx += 1;
-            } else if tx > hx {
-                tx -= 1;
-            }
-
-            if ty < hy {
-                ty += 1;
-            } else if ty > hy {
-                ty -= 1;
-            }
+pub fn follow_path(head: &[Pos]) -> Vec<Pos> {
+    let mut tail = Pos::default();
+    let mut path = vec![tail];
+
+    for &knot in head {
+        if tail.chebyshev(knot) > 1 {
+            tail += (knot - tail).signum();
         }
 
-        tail.push([tx, ty]);
+        path.push(tail);
     }
 
-    tail
+    path
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let head = parse_moves(lines);
-    let mut tail = follow_path(&head);
+pub struct Day09;
 
-    println!("part A: {}", tail.iter().collect::<HashSet<_>>().len());
+impl Puzzle for Day09 {
+    type Input = Vec<Pos>;
 
-    for _ in 0..8 {
-        tail = follow_path(&tail);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_moves(&lines(input))
     }
 
-    println!("part B: {}", tail.iter().collect::<HashSet<_>>().len());
+    fn part_a(head: &Self::Input) -> Result<Answer> {
+        let tail = follow_path(head);
+        Ok(tail.iter().collect::<HashSet<_>>().len().into())
+    }
+
+    fn part_b(head: &Self::Input) -> Result<Answer> {
+        let mut tail = follow_path(head);
 
-    Ok(())
+        for _ in 0..8 {
+            tail = follow_path(&tail);
+        }
+
+        Ok(tail.iter().collect::<HashSet<_>>().len().into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day09.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day09::parse(EXAMPLE).unwrap();
+        assert_eq!(Day09::part_a(&input).unwrap(), 13.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day09::parse(EXAMPLE).unwrap();
+        assert_eq!(Day09::part_b(&input).unwrap(), 1.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day09>(9);
     }
 }
@@ -1,69 +1,95 @@
 use crate::common::*;
-use ndarray::Array2;
 
-fn simulate(lines: Lines) -> Vec<i32> {
+pub fn parse_instruction(line: &str) -> Result<Option<i32>> {
+    if line == "noop" {
+        Ok(None)
+    } else {
+        let (value,) =
+            extract_regex(regex!("^addx (-?[0-9]+)$"), line).context("expected noop or addx")?;
+        Ok(Some(value))
+    }
+}
+
+pub fn simulate(lines: Lines) -> Result<Vec<i32>> {
     let mut reg = 1;
     let mut cycles = vec![];
 
-    for &line in lines {
-        if line == "noop" {
-            cycles.push(reg);
-        } else if let Some(matches) = find_regex("addx (-?[0-9]+)", line) {
-            cycles.push(reg);
+    for instr in parse_lines(lines, parse_instruction)? {
+        cycles.push(reg);
+
+        if let Some(delta) = instr {
             cycles.push(reg);
-            reg += matches[1].parse::<i32>().unwrap();
-        } else {
-            panic!("invalid line: {}", line);
+            reg += delta;
         }
     }
 
-    cycles
+    Ok(cycles)
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let cycles = simulate(lines);
+pub struct Day10;
 
-    let indices = [20, 60, 100, 140, 180, 220];
-    let sum = indices
-        .iter()
-        .map(|&i| i * cycles[i as usize - 1])
-        .sum::<i32>();
-    println!("part A: {}", sum);
+impl Puzzle for Day10 {
+    type Input = Vec<i32>;
 
-    let (width, height) = (40, 6);
-    let mut screen = Array2::from_elem((width, height), '.');
-    for (cycle, reg) in enumerate(cycles) {
-        let x = cycle % width;
-        let y = cycle / width;
+    fn parse(input: &str) -> Result<Self::Input> {
+        simulate(&lines(input))
+    }
 
-        if (reg - x as i32).abs() <= 1 {
-            screen[[x, y]] = '#';
-        }
+    fn part_a(cycles: &Self::Input) -> Result<Answer> {
+        let indices = [20, 60, 100, 140, 180, 220];
+        let sum = indices
+            .iter()
+            .map(|&i| i * cycles[i as usize - 1])
+            .sum::<i32>();
+
+        Ok(sum.into())
     }
 
-    let img = screen
-        .columns()
-        .into_iter()
-        .map(|r| r.iter().join(""))
-        .join("\n");
+    fn part_b(cycles: &Self::Input) -> Result<Answer> {
+        let (width, height) = (40, 6);
+        let mut screen = Grid::new(height, width, '.');
+        for (cycle, &reg) in enumerate(cycles) {
+            let x = cycle % width;
+            let y = cycle / width;
 
-    println!("part B:");
-    println!("{}", img);
+            if (reg - x as i32).abs() <= 1 {
+                screen[[y, x]] = '#';
+            }
+        }
 
-    Ok(())
+        Ok(Answer::Image(screen.to_string()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day10.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day10::parse(EXAMPLE).unwrap();
+        assert_eq!(Day10::part_a(&input).unwrap(), 13140.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day10::parse(EXAMPLE).unwrap();
+        let image = Answer::image([
+            "##..##..##..##..##..##..##..##..##..##..",
+            "###...###...###...###...###...###...###.",
+            "####....####....####....####....####....",
+            "#####.....#####.....#####.....#####.....",
+            "######......######......######......####",
+            "#######.......#######.......#######.....",
+        ]);
+
+        assert_eq!(Day10::part_b(&input).unwrap(), image);
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day10>(10);
     }
 }
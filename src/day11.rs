@@ -2,25 +2,24 @@ use crate::common::*;
 use std::cmp::Reverse;
 use std::collections::VecDeque;
 use std::mem::take;
-use std::str::FromStr;
 
-enum Operation {
+pub enum Operation {
     Add,
     Mul,
 }
 
-enum Operand {
+pub enum Operand {
     Constant(i64),
     Old,
 }
 
-struct Monkey {
-    starting_items: Vec<i64>,
-    operation: Operation,
-    operand: Operand,
-    divisible: i64,
-    if_true: usize,
-    if_false: usize,
+pub struct Monkey {
+    pub starting_items: Vec<i64>,
+    pub operation: Operation,
+    pub operand: Operand,
+    pub divisible: i64,
+    pub if_true: usize,
+    pub if_false: usize,
 }
 
 impl Monkey {
@@ -37,54 +36,78 @@ impl Monkey {
     }
 }
 
-fn regex_number<T: FromStr>(pattern: &str, input: &str) -> Result<T> {
-    Ok(find_regex(pattern, input)
-        .ok_or_else(|| anyhow!("line {:?} failed regex {:?}", input, pattern))?
-        .get(1)
-        .unwrap()
-        .as_str()
-        .parse()
-        .unwrap_or_else(|_| unreachable!()))
+fn parse_items(line: &str) -> Result<Vec<i64>> {
+    let (items,): (String,) = extract_regex(regex!("Starting items: ([0-9, ]*)$"), line)
+        .context("expected a list of starting items")?;
+
+    parse_list(&items, ',')
 }
 
-fn parse_monkeys(lines: Lines) -> Result<Vec<Monkey>> {
-    let mut i = 0;
-    let mut monkeys = vec![];
+fn parse_operation(line: &str) -> Result<(Operation, Operand)> {
+    let (operation, operand): (char, String) =
+        extract_regex(regex!("Operation: new = old ([*+]) ([0-9]+|old)$"), line)
+            .context("expected an operation")?;
 
-    while i < lines.len() {
-        let divisible = regex_number("divisible by ([0-9]+)", lines[i + 3])?;
-        let if_true = regex_number("If true: throw to monkey ([0-9]+)", lines[i + 4])?;
-        let if_false = regex_number("If false: throw to monkey ([0-9]+)", lines[i + 5])?;
+    let operation = match operation {
+        '*' => Operation::Mul,
+        '+' => Operation::Add,
+        _ => unreachable!(),
+    };
 
-        let starting_items = find_regex_all("[0-9]+", lines[i + 1])
-            .into_iter()
-            .map(|e| e[0].parse::<i64>())
-            .try_collect()?;
+    let operand = match operand.as_str() {
+        "old" => Operand::Old,
+        v => Operand::Constant(v.parse()?),
+    };
 
-        let line = &lines[i + 2];
-        let matches = find_regex("Operation: new = old ([*+]) ([0-9]+|old)", line).unwrap();
+    Ok((operation, operand))
+}
 
-        let operation = match &matches[1] {
-            "*" => Operation::Mul,
-            "+" => Operation::Add,
-            _ => unreachable!(),
-        };
+/// Parses the lines describing one monkey; missing lines count as empty.
+fn parse_monkey(block: Lines) -> Result<Monkey> {
+    let line = |k: usize| block.get(k).copied().unwrap_or_default();
+
+    let starting_items = at_line(1, line(1), parse_items(line(1)))?;
+    let (operation, operand) = at_line(2, line(2), parse_operation(line(2)))?;
+    let (divisible,) = at_line(
+        3,
+        line(3),
+        extract_regex(regex!("divisible by ([0-9]+)"), line(3)),
+    )?;
+    let (if_true,) = at_line(
+        4,
+        line(4),
+        extract_regex(regex!("If true: throw to monkey ([0-9]+)"), line(4)),
+    )?;
+    let (if_false,) = at_line(
+        5,
+        line(5),
+        extract_regex(regex!("If false: throw to monkey ([0-9]+)"), line(5)),
+    )?;
+
+    Ok(Monkey {
+        starting_items,
+        operation,
+        operand,
+        divisible,
+        if_true,
+        if_false,
+    })
+}
 
-        let operand = match &matches[2] {
-            "old" => Operand::Old,
-            v => Operand::Constant(v.parse().unwrap()),
-        };
+pub fn parse_monkeys(lines: Lines) -> Result<Vec<Monkey>> {
+    let mut monkeys = vec![];
 
-        monkeys.push(Monkey {
-            starting_items,
-            operation,
-            operand,
-            divisible,
-            if_true,
-            if_false,
-        });
+    for i in (0..lines.len()).step_by(7) {
+        monkeys.push(shift_lines(i, parse_monkey(&lines[i..]))?);
+    }
 
-        i += 7;
+    for (index, monkey) in enumerate(&monkeys) {
+        for (k, target) in [(4, monkey.if_true), (5, monkey.if_false)] {
+            if target >= monkeys.len() {
+                let i = 7 * index + k;
+                at_line(i, lines[i], Err(anyhow!("unknown monkey {}", target)))?;
+            }
+        }
     }
 
     Ok(monkeys)
@@ -102,7 +125,7 @@ fn lcm(first: i64, second: i64) -> i64 {
     first * second / gcd(first, second)
 }
 
-fn simulate(monkeys: &[Monkey], rounds: usize, divide: i64) -> usize {
+pub fn simulate(monkeys: &[Monkey], rounds: usize, divide: i64) -> usize {
     let mut items = vec![];
     let mut inspected = vec![];
     let mut factor = 1;
@@ -134,29 +157,44 @@ fn simulate(monkeys: &[Monkey], rounds: usize, divide: i64) -> usize {
     inspected[0] * inspected[1]
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let monkeys = parse_monkeys(lines)?;
+pub struct Day11;
 
-    let score = simulate(&monkeys, 20, 3);
-    println!("part A: {}", score);
+impl Puzzle for Day11 {
+    type Input = Vec<Monkey>;
 
-    let score = simulate(&monkeys, 10000, 1);
-    println!("part B: {}", score);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_monkeys(&lines(input))
+    }
 
-    Ok(())
+    fn part_a(monkeys: &Self::Input) -> Result<Answer> {
+        Ok(simulate(monkeys, 20, 3).into())
+    }
+
+    fn part_b(monkeys: &Self::Input) -> Result<Answer> {
+        Ok(simulate(monkeys, 10000, 1).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day11.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(Day11::part_a(&input).unwrap(), 10605.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(Day11::part_b(&input).unwrap(), 2713310158u64.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day11>(11);
     }
 }
@@ -1,87 +1,95 @@
 use crate::common::*;
-use std::collections::VecDeque;
-
-type Pos = [i32; 2];
-
-fn parse_grid(lines: Lines) -> (HashMap<Pos, i32>, Pos, Pos) {
-    let mut grid = HashMap::default();
-    let mut start = Pos::default();
-    let mut end = Pos::default();
-
-    for (i, &line) in enumerate(lines) {
-        for (j, c) in line.chars().enumerate() {
-            let p = [i as i32, j as i32];
-
-            let height = match c {
-                'a'..='z' => c as i32 - 'a' as i32,
-                'S' => {
-                    start = p;
-                    0
-                }
-                'E' => {
-                    end = p;
-                    25
-                }
-                _ => panic!("invalid character: {:?}", c),
-            };
-
-            grid.insert(p, height);
-        }
-    }
 
-    (grid, start, end)
+pub type Pos = [usize; 2];
+
+pub fn parse_grid(lines: Lines) -> Result<(Grid<i32>, Pos, Pos)> {
+    let chars = Grid::parse(lines, |c| match c {
+        'a'..='z' | 'S' | 'E' => Ok(c),
+        _ => bail!("expected a height"),
+    })?;
+
+    let find = |target| {
+        chars
+            .indexed_iter()
+            .find(|&(_, &c)| c == target)
+            .map(|(p, _)| p)
+            .ok_or_else(|| anyhow!("missing {:?} in height map", target))
+    };
+
+    let start = find('S')?;
+    let end = find('E')?;
+    let heights = chars.map(|&c| match c {
+        'S' => 0,
+        'E' => 25,
+        _ => c as i32 - 'a' as i32,
+    });
+
+    Ok((heights, start, end))
 }
 
-fn calculate_dists(heights: &HashMap<Pos, i32>, start: Pos) -> HashMap<Pos, i32> {
-    let mut dists = HashMap::default();
-    let mut queue = VecDeque::new();
-
-    dists.insert(start, 0);
-    queue.push_back(start);
+/// Searches backwards from `end`, so every edge goes down at most one step.
+pub fn calculate_dists(heights: &Grid<i32>, end: Pos) -> Search<Pos, usize> {
+    let successors = |&pos: &Pos| {
+        heights
+            .neighbors4(pos)
+            .filter(move |&n| heights[pos] - heights[n] <= 1)
+    };
 
-    while let Some(pos) = queue.pop_front() {
-        for [dx, dy] in [[-1, 0], [1, 0], [0, -1], [0, 1]] {
-            let neighbor = [pos[0] + dx, pos[1] + dy];
+    bfs([end], successors, |_| false)
+}
 
-            if heights.contains_key(&neighbor) && !dists.contains_key(&neighbor) {
-                if heights[&pos] - heights[&neighbor] <= 1 {
-                    dists.insert(neighbor, dists[&pos] + 1);
-                    queue.push_back(neighbor);
-                }
-            }
-        }
-    }
+pub struct Day12;
 
-    dists
-}
+impl Puzzle for Day12 {
+    type Input = (Grid<i32>, Pos, Pos);
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let (heights, start, end) = parse_grid(lines);
-    let dists = calculate_dists(&heights, end);
-    println!("part A: {:?}", dists[&start]);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_grid(&lines(input))
+    }
 
-    let (_, best_dist) = dists
-        .iter()
-        .filter(|(&k, _)| heights[&k] == 0)
-        .min_by_key(|(_, &v)| v)
-        .unwrap();
+    fn part_a((heights, start, end): &Self::Input) -> Result<Answer> {
+        let dists = calculate_dists(heights, *end);
+        let dist = dists
+            .dist(start)
+            .ok_or_else(|| anyhow!("no path from start to end"))?;
 
-    println!("part B: {:?}", best_dist);
+        Ok(dist.into())
+    }
 
-    Ok(())
+    fn part_b((heights, _, end): &Self::Input) -> Result<Answer> {
+        let dists = calculate_dists(heights, *end);
+        let best_dist = dists
+            .dists
+            .iter()
+            .filter(|&(&p, _)| heights[p] == 0)
+            .map(|(_, &d)| d)
+            .min()
+            .ok_or_else(|| anyhow!("no path from any lowest point to end"))?;
+
+        Ok(best_dist.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day12.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day12::parse(EXAMPLE).unwrap();
+        assert_eq!(Day12::part_a(&input).unwrap(), 31.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day12::parse(EXAMPLE).unwrap();
+        assert_eq!(Day12::part_b(&input).unwrap(), 29.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day12>(12);
     }
 }
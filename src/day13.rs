@@ -6,7 +6,7 @@ use std::iter::zip;
 use std::mem::take;
 
 #[derive(Clone, Eq, PartialEq)]
-enum Item {
+pub enum Item {
     Int(i64),
     List(Vec<Item>),
 }
@@ -48,73 +48,99 @@ impl PartialOrd for Item {
     }
 }
 
-fn parse_item(line: &str) -> Item {
+pub fn parse_item(line: &str) -> Result<Item> {
     let mut stack: Vec<Vec<Item>> = default();
     let mut current: Vec<Item> = default();
-    let mut chars = line.chars().peekable();
+    let mut chars = line.chars().enumerate().peekable();
 
-    while let Some(c) = chars.next() {
+    while let Some((column, c)) = chars.next() {
         if c == '[' {
             stack.push(take(&mut current));
-        } else if c.is_digit(10) {
-            let mut i = c.to_digit(10).unwrap() as i64;
-            while let Some(c) = chars.next_if(|c| c.is_digit(10)) {
+        } else if let Some(digit) = c.to_digit(10) {
+            let mut i = digit as i64;
+            while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
                 i = (i * 10) + c.to_digit(10).unwrap() as i64;
             }
 
             current.push(Item::Int(i));
         } else if c == ']' {
-            let mut last = stack.pop().unwrap();
+            let Some(mut last) = stack.pop() else {
+                return Err(error_at(line, column, "unmatched ']'"));
+            };
+
             last.push(Item::List(current));
             current = last;
+        } else if c != ',' {
+            return Err(error_at(line, column, format!("unexpected {:?}", c)));
         }
     }
 
-    Item::List(current)
+    if !stack.is_empty() {
+        return Err(error_at(line, line.chars().count(), "unclosed '['"));
+    }
+
+    Ok(Item::List(current))
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let mut count = 0;
+pub struct Day13;
 
-    for i in (0..lines.len()).step_by(3) {
-        let left = parse_item(lines[i]);
-        let right = parse_item(lines[i + 1]);
+impl Puzzle for Day13 {
+    type Input = Vec<Item>;
 
-        if left < right {
-            count += i / 3 + 1;
-        }
+    fn parse(input: &str) -> Result<Self::Input> {
+        let packets = parse_lines(&lines(input), |line| match line {
+            "" => Ok(None),
+            _ => parse_item(line).map(Some),
+        })?;
+
+        Ok(packets.into_iter().flatten().collect())
     }
 
-    println!("part A: {:?}", count);
+    fn part_a(packets: &Self::Input) -> Result<Answer> {
+        let mut count = 0;
+
+        for (i, pair) in enumerate(packets.chunks(2)) {
+            if pair[0] < pair[1] {
+                count += i + 1;
+            }
+        }
 
-    let mut packets = lines
-        .iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| parse_item(line))
-        .collect_vec();
+        Ok(count.into())
+    }
 
-    let decoders = [parse_item("[[2]]"), parse_item("[[6]]")];
-    packets.extend(cloned(&decoders));
-    packets.sort();
+    fn part_b(packets: &Self::Input) -> Result<Answer> {
+        let mut packets = packets.clone();
+        let decoders = [parse_item("[[2]]")?, parse_item("[[6]]")?];
+        packets.extend(cloned(&decoders));
+        packets.sort();
 
-    let a = packets.iter().position(|v| v == &decoders[0]).unwrap() + 1;
-    let b = packets.iter().position(|v| v == &decoders[1]).unwrap() + 1;
-    println!("part B: {:?}", a * b);
+        let a = packets.iter().position(|v| v == &decoders[0]).unwrap() + 1;
+        let b = packets.iter().position(|v| v == &decoders[1]).unwrap() + 1;
 
-    Ok(())
+        Ok((a * b).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day13.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day13::parse(EXAMPLE).unwrap();
+        assert_eq!(Day13::part_a(&input).unwrap(), 13.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day13::parse(EXAMPLE).unwrap();
+        assert_eq!(Day13::part_b(&input).unwrap(), 140.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day13>(13);
     }
 }
@@ -1,77 +1,129 @@
 use crate::common::*;
-use std::cmp;
 
-type Point = [i32; 2];
+pub type Point = Point2<i64>;
 
-fn parse_points(line: &str) -> impl Iterator<Item = Point> + '_ {
-    find_regex_all("([0-9]+),([0-9]+)", line)
-        .into_iter()
-        .map(|m| [m[1].parse().unwrap(), m[2].parse().unwrap()])
+pub fn parse_path(line: &str) -> Result<Vec<Point>> {
+    let mut column = 0;
+    let mut points = vec![];
+
+    for part in line.split(" -> ") {
+        let point = match part.split_once(',') {
+            Some((x, y)) => x.parse().ok().zip(y.parse().ok()),
+            None => None,
+        };
+
+        let Some((x, y)) = point else {
+            return Err(error_at(line, column, "expected a point"));
+        };
+
+        points.push(Point::new(x, y));
+        column += part.len() + " -> ".len();
+    }
+
+    Ok(points)
 }
 
-fn build_cave(lines: Lines) -> HashSet<Point> {
-    let mut rocks = HashSet::default();
-
-    for &line in lines {
-        for (a, b) in parse_points(line).tuple_windows() {
-            if a[0] == b[0] {
-                let x = a[0];
-                for y in cmp::min(a[1], b[1])..=cmp::max(a[1], b[1]) {
-                    rocks.insert([x, y]);
-                }
-            } else if a[1] == b[1] {
-                let y = a[1];
-                for x in cmp::min(a[0], b[0])..=cmp::max(a[0], b[0]) {
-                    rocks.insert([x, y]);
-                }
+pub fn build_cave(lines: Lines) -> Result<SparseGrid<char>> {
+    let paths = parse_lines(lines, parse_path)?;
+    let mut rocks = SparseGrid::new();
+
+    for path in paths {
+        for (a, b) in path.into_iter().tuple_windows() {
+            if a.x != b.x && a.y != b.y {
+                continue;
+            }
+
+            let step = (b - a).signum();
+            let mut p = a;
+            rocks.insert(p, '#');
+
+            while p != b {
+                p += step;
+                rocks.insert(p, '#');
             }
         }
     }
 
-    rocks
+    Ok(rocks)
 }
 
-fn drop_sand(objects: &HashSet<Point>, floor: i32, has_void: bool) -> Option<Point> {
-    let [mut x, mut y] = [500, 0];
+pub fn drop_sand(objects: &SparseGrid<char>, floor: i64, has_void: bool) -> Option<Point> {
+    let mut p = Point::new(500, 0);
 
-    if objects.contains(&[x, y]) {
+    if objects.contains(p) {
         return None;
     }
 
-    loop {
-        if y + 1 == floor {
-            break if has_void { None } else { Some([x, y]) };
-        } else if !objects.contains(&[x, y + 1]) {
-            y += 1;
-        } else if !objects.contains(&[x - 1, y + 1]) {
-            x -= 1;
-            y += 1;
-        } else if !objects.contains(&[x + 1, y + 1]) {
-            x += 1;
-            y += 1;
-        } else {
-            break Some([x, y]);
+    'falling: loop {
+        if p.y + 1 == floor {
+            break if has_void { None } else { Some(p) };
         }
+
+        for dir in [Dir8::S, Dir8::SW, Dir8::SE] {
+            if !objects.contains(p + dir.delta()) {
+                p += dir.delta();
+                continue 'falling;
+            }
+        }
+
+        break Some(p);
     }
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let rocks = build_cave(lines);
-    let floor = rocks.iter().map(|&[_, y]| y).max().unwrap() + 2;
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    type Input = SparseGrid<char>;
 
-    let mut objects = rocks.clone();
-    while let Some(p) = drop_sand(&objects, floor, true) {
-        objects.insert(p);
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_cave(&lines(input))
     }
 
-    println!("part A: {}", objects.len() - rocks.len());
+    fn part_a(rocks: &Self::Input) -> Result<Answer> {
+        let [_, bottom] = rocks.bounds().ok_or_else(|| anyhow!("cave has no rocks"))?;
+        let floor = bottom.y + 2;
 
-    let mut objects = rocks.clone();
-    while let Some(p) = drop_sand(&objects, floor, false) {
-        objects.insert(p);
+        let mut objects = rocks.clone();
+        while let Some(p) = drop_sand(&objects, floor, true) {
+            objects.insert(p, 'o');
+        }
+
+        Ok((objects.len() - rocks.len()).into())
+    }
+
+    fn part_b(rocks: &Self::Input) -> Result<Answer> {
+        let [_, bottom] = rocks.bounds().ok_or_else(|| anyhow!("cave has no rocks"))?;
+        let floor = bottom.y + 2;
+
+        let mut objects = rocks.clone();
+        while let Some(p) = drop_sand(&objects, floor, false) {
+            objects.insert(p, 'o');
+        }
+
+        Ok((objects.len() - rocks.len()).into())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("part B: {}", objects.len() - rocks.len());
+    const EXAMPLE: &str = include_str!("../inputs/day14.example");
+
+    #[test]
+    fn test_a() {
+        let input = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(Day14::part_a(&input).unwrap(), 24.into());
+    }
 
-    Ok(())
+    #[test]
+    fn test_b() {
+        let input = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(Day14::part_b(&input).unwrap(), 93.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day14>(14);
+    }
 }
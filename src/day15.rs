@@ -1,7 +1,6 @@
 use crate::common::*;
 use recap::Recap;
 use serde::Deserialize;
-use std::ops::RangeInclusive;
 
 #[derive(Recap, Deserialize)]
 #[recap(
@@ -15,81 +14,112 @@ struct Line {
 }
 
 #[derive(Debug)]
-struct Sensor {
-    x: i64,
-    y: i64,
-    radius: i64,
+pub struct Sensor {
+    pub pos: Point2<i64>,
+    pub beacon: Point2<i64>,
+    pub radius: i64,
 }
 
-fn parse_sensors(lines: Lines) -> Vec<Sensor> {
-    lines
+pub fn parse_sensors(lines: Lines) -> Result<Vec<Sensor>> {
+    parse_lines(lines, |line| {
+        let l = line.parse::<Line>().context("expected a sensor report")?;
+        let pos = Point2::new(l.sx, l.sy);
+        let beacon = Point2::new(l.bx, l.by);
+
+        Ok(Sensor {
+            pos,
+            beacon,
+            radius: pos.manhattan(beacon),
+        })
+    })
+}
+
+/// Positions on row `y` that are within range of at least one sensor.
+pub fn covered_positions(sensors: &[Sensor], y: i64) -> IntervalSet<i64> {
+    sensors
         .iter()
-        .map(|&l| l.parse::<Line>().unwrap())
-        .map(|l| Sensor {
-            x: l.sx,
-            y: l.sy,
-            radius: (l.sx - l.bx).abs() + (l.sy - l.by).abs(),
+        .map(|sensor| {
+            let dx = sensor.radius - (y - sensor.pos.y).abs();
+            (sensor.pos.x - dx)..=(sensor.pos.x + dx)
         })
         .collect()
 }
 
-fn nonbeacon_position(sensors: &[Sensor], y: i64) -> (i64, Vec<RangeInclusive<i64>>) {
-    let mut d = vec![];
-    d.reserve(sensors.len() * 2);
+pub fn nonbeacon_positions(sensors: &[Sensor], y: i64) -> i64 {
+    let beacons = sensors
+        .iter()
+        .map(|s| s.beacon)
+        .filter(|b| b.y == y)
+        .unique()
+        .count();
+
+    covered_positions(sensors, y).len() - beacons as i64
+}
 
-    for sensor in sensors {
-        let dy = (y - sensor.y).abs();
-        let dx = sensor.radius - dy;
+pub fn find_distress_beacon(sensors: &[Sensor], size: i64) -> Option<Point2<i64>> {
+    (0..=size).find_map(|y| {
+        let gaps = covered_positions(sensors, y).complement(0..=size);
+        let x = *gaps.iter().next()?.start();
 
-        if dx > 0 {
-            d.push((sensor.x - dx, 1));
-            d.push((sensor.x + dx, -1));
-        }
+        Some(Point2::new(x, y))
+    })
+}
+
+/// Row to inspect for part A and upper bound on the coordinates of the
+/// distress beacon. The example uses smaller values than the real inputs and
+/// is recognised by all of its sensors lying within `0..=20`.
+pub fn puzzle_bounds(sensors: &[Sensor]) -> (i64, i64) {
+    if sensors.iter().all(|s| s.pos.x <= 20 && s.pos.y <= 20) {
+        (10, 20)
+    } else {
+        (2000000, 4000000)
     }
+}
 
-    d.sort();
+pub struct Day15;
 
-    if d.is_empty() {
-        return (0, vec![]);
+impl Puzzle for Day15 {
+    type Input = Vec<Sensor>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_sensors(&lines(input))
     }
 
-    let mut overlaps = 0;
-    let mut last_x = d[0].0;
-    let mut total = 0;
-    let mut segments = Vec::with_capacity(1);
-    let mut segment_start = 0;
-
-    for &(x, p) in &d {
-        if overlaps > 0 {
-            total += x - last_x;
-            last_x = x;
-        } else {
-            segment_start = x;
-        }
-
-        overlaps += p;
-
-        if overlaps == 0 {
-            segments.push(segment_start..=x);
-        }
+    fn part_a(sensors: &Self::Input) -> Result<Answer> {
+        let (row, _) = puzzle_bounds(sensors);
+        Ok(nonbeacon_positions(sensors, row).into())
     }
 
-    (total, segments)
+    fn part_b(sensors: &Self::Input) -> Result<Answer> {
+        let (_, size) = puzzle_bounds(sensors);
+        let p = find_distress_beacon(sensors, size)
+            .ok_or_else(|| anyhow!("no position found for the distress beacon"))?;
+
+        Ok((p.x * 4000000 + p.y).into())
+    }
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let sensors = parse_sensors(lines);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("part A: {:?}", nonbeacon_position(&sensors, 2000000));
+    const EXAMPLE: &str = include_str!("../inputs/day15.example");
 
-    for y in 0..=4000000 {
-        let (_, segments) = nonbeacon_position(&sensors, y);
+    #[test]
+    fn test_a() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        assert_eq!(puzzle_bounds(&input), (10, 20));
+        assert_eq!(Day15::part_a(&input).unwrap(), 26.into());
+    }
 
-        if segments.len() > 1 {
-            let x = (segments[0].end() + segments[1].start()) / 2;
-            println!("part B: x={:?} y={:?} freq={}", x, y, x * 4000000 + y);
-        }
+    #[test]
+    fn test_b() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        assert_eq!(Day15::part_b(&input).unwrap(), 56000011.into());
     }
 
-    Ok(())
+    #[test]
+    fn test_input() {
+        check_input::<Day15>(15);
+    }
 }
@@ -2,7 +2,6 @@ use crate::common::*;
 use recap::Recap;
 use serde::Deserialize;
 use std::collections::hash_map::Entry;
-use std::collections::VecDeque;
 
 #[derive(Debug, Recap, Deserialize)]
 #[recap(
@@ -15,46 +14,51 @@ struct Line {
 }
 
 #[derive(Debug)]
-struct Node {
+pub struct Node {
     // name: String,
-    rate: i64,
-    dists: Vec<i64>,
+    pub rate: i64,
+    pub dists: Vec<i64>,
 }
 
-fn parse_cave(lines: Lines) -> Vec<Node> {
-    let lines = lines
+pub fn parse_cave(input: Lines) -> Result<Vec<Node>> {
+    let parsed = parse_lines(input, |line| {
+        line.parse::<Line>().context("expected a valve report")
+    })?;
+
+    let names = parsed
         .iter()
-        .map(|&line| line.parse::<Line>().unwrap())
+        .map(|l| l.name.as_str())
+        .collect::<HashSet<_>>();
+
+    for (index, l) in enumerate(&parsed) {
+        if let Some(n) = l.neighbors.split(", ").find(|n| !names.contains(n)) {
+            at_line(index, input[index], Err(anyhow!("unknown valve {:?}", n)))?;
+        }
+    }
+
+    let lines = parsed
+        .into_iter()
         .map(|l| (l.name.clone(), l))
         .collect::<HashMap<String, _>>();
 
+    ensure!(lines.contains_key("AA"), "no valve named AA");
+
     let node_names = lines
         .values()
         .filter(|l| l.rate > 0 || l.name == "AA")
         .map(|l| l.name.as_str())
         .sorted()
         .collect_vec();
+    ensure!(node_names.len() <= 64, "too many valves with a flow rate");
     let mut nodes = vec![];
 
     for &name in &node_names {
-        let mut dists = vec![-1; node_names.len()];
-        let mut visited = HashSet::default();
-        let mut queue = VecDeque::new();
-        queue.push_back((name, 0));
-
-        while let Some((other, dist)) = queue.pop_front() {
-            if !visited.insert(other) {
-                continue;
-            }
-
-            if let Some(index) = node_names.iter().position(|n| n == &other) {
-                dists[index] = dist;
-            }
-
-            for neighbor in lines[&*other].neighbors.split(", ") {
-                queue.push_back((neighbor, dist + 1));
-            }
-        }
+        let tunnels = |&n: &&str| lines[n].neighbors.split(", ");
+        let search = bfs([name], tunnels, |_| false);
+        let dists = node_names
+            .iter()
+            .map(|n| search.dist(n).map_or(-1, |d| d as i64))
+            .collect();
 
         nodes.push(Node {
             // name: name.to_string(),
@@ -63,11 +67,9 @@ fn parse_cave(lines: Lines) -> Vec<Node> {
         });
     }
 
-    nodes
+    Ok(nodes)
 }
 
-const N: usize = 16;
-
 #[derive(Debug, Copy, Clone)]
 struct State {
     position: usize,
@@ -77,7 +79,6 @@ struct State {
 }
 
 fn compute_states(nodes: &[Node], max_time: i64) -> Vec<State> {
-    assert_eq!(nodes.len(), N);
     let initial_state = State {
         position: 0,
         time: 0,
@@ -91,14 +92,14 @@ fn compute_states(nodes: &[Node], max_time: i64) -> Vec<State> {
     while let Some(&state) = options.get(index) {
         index += 1;
 
-        for i in 0..N {
+        for i in 0..nodes.len() {
             let mask = 1 << i;
 
             if (state.is_opened) & mask == 0 {
                 let new_time = state.time + nodes[state.position].dists[i] + 1;
 
                 if new_time < max_time {
-                    let mut new_state = state.clone();
+                    let mut new_state = state;
                     new_state.position = i;
                     new_state.time = new_time;
                     new_state.is_opened |= mask;
@@ -112,7 +113,7 @@ fn compute_states(nodes: &[Node], max_time: i64) -> Vec<State> {
     options
 }
 
-fn solve(nodes: &[Node], max_time: i64) -> i64 {
+pub fn solve(nodes: &[Node], max_time: i64) -> i64 {
     compute_states(nodes, max_time)
         .iter()
         .map(|s| s.total_flow)
@@ -120,7 +121,7 @@ fn solve(nodes: &[Node], max_time: i64) -> i64 {
         .unwrap()
 }
 
-fn solve_with_elephant(nodes: &[Node], max_time: i64) -> i64 {
+pub fn solve_with_elephant(nodes: &[Node], max_time: i64) -> i64 {
     let mut best_states = HashMap::default();
 
     for state in compute_states(nodes, max_time) {
@@ -142,30 +143,44 @@ fn solve_with_elephant(nodes: &[Node], max_time: i64) -> i64 {
         .unwrap()
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let cave = parse_cave(lines);
+pub struct Day16;
 
-    let flow = solve(&cave, 30);
-    println!("part A: {}", flow);
+impl Puzzle for Day16 {
+    type Input = Vec<Node>;
 
-    //2090 -> to low
-    let flow = solve_with_elephant(&cave, 26);
-    println!("part B: {}", flow);
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_cave(&lines(input))
+    }
+
+    fn part_a(cave: &Self::Input) -> Result<Answer> {
+        Ok(solve(cave, 30).into())
+    }
 
-    Ok(())
+    fn part_b(cave: &Self::Input) -> Result<Answer> {
+        Ok(solve_with_elephant(cave, 26).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day16.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day16::parse(EXAMPLE).unwrap();
+        assert_eq!(Day16::part_a(&input).unwrap(), 1651.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day16::parse(EXAMPLE).unwrap();
+        assert_eq!(Day16::part_b(&input).unwrap(), 1707.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day16>(16);
     }
 }
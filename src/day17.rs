@@ -37,143 +37,161 @@ const ROCKS: [Rock; 5] = [
     ],
 ];
 
-fn rock_intersects_anything(rx: i64, ry: i64, rock: &Rock, solids: &HashSet<(i64, i64)>) -> bool {
-    for dx in 0..ROCK_SIZE {
-        for dy in 0..ROCK_SIZE {
-            if rock[(ROCK_SIZE - dy - 1) as usize][dx as usize] == '#' {
-                let (x, y) = (rx + dx, ry + dy);
-
-                if x < 0 || x >= CAVE_WIDTH {
-                    return true;
-                }
-
-                if y < 0 {
-                    return true;
-                }
-
-                if solids.contains(&(x, y)) {
-                    return true;
-                }
-            }
-        }
-    }
-
-    false
+// Height grows upwards in the cave, so falling decreases `y`.
+const DOWN: Point2<i64> = Point2::new(0, -1);
+
+fn rock_cells(pos: Point2<i64>, rock: &Rock) -> impl Iterator<Item = Point2<i64>> + '_ {
+    (0..ROCK_SIZE)
+        .cartesian_product(0..ROCK_SIZE)
+        .filter(|&(dx, dy)| rock[(ROCK_SIZE - dy - 1) as usize][dx as usize] == '#')
+        .map(move |(dx, dy)| pos + Point2::new(dx, dy))
 }
 
-fn insert_rock(rx: i64, ry: i64, rock: &Rock, solid: &mut HashSet<(i64, i64)>) -> i64 {
-    let mut highest = 0;
+fn rock_intersects_anything(pos: Point2<i64>, rock: &Rock, solids: &SparseGrid<char>) -> bool {
+    rock_cells(pos, rock).any(|p| !(0..CAVE_WIDTH).contains(&p.x) || p.y < 0 || solids.contains(p))
+}
 
-    for dx in 0..ROCK_SIZE {
-        for dy in 0..ROCK_SIZE {
-            if rock[(ROCK_SIZE - dy - 1) as usize][dx as usize] == '#' {
-                let (sx, sy) = (rx + dx, ry + dy);
-                solid.insert((sx, sy));
-                highest = highest.max(sy);
-            }
-        }
+fn insert_rock(pos: Point2<i64>, rock: &Rock, solid: &mut SparseGrid<char>) {
+    for p in rock_cells(pos, rock) {
+        solid.insert(p, '#');
     }
-
-    highest
 }
 
 fn drop_rock(
-    mut x: i64,
-    mut y: i64,
+    mut pos: Point2<i64>,
     jets: &[char],
     jet_index: &mut usize,
     rock: &Rock,
-    solid: &HashSet<(i64, i64)>,
-) -> (i64, i64) {
+    solid: &SparseGrid<char>,
+) -> Point2<i64> {
     loop {
-        match jets[(*jet_index) % jets.len()] {
-            '<' => {
-                if !rock_intersects_anything(x - 1, y, rock, &solid) {
-                    x -= 1;
-                }
-            }
-            '>' => {
-                if !rock_intersects_anything(x + 1, y, rock, &solid) {
-                    x += 1;
-                }
-            }
+        let push = match jets[(*jet_index) % jets.len()] {
+            '<' => Dir4::W.delta(),
+            '>' => Dir4::E.delta(),
             c => {
                 panic!("invalid jet {}", c)
             }
+        };
+
+        if !rock_intersects_anything(pos + push, rock, solid) {
+            pos += push;
         }
 
         *jet_index += 1;
 
-        if rock_intersects_anything(x, y - 1, rock, &solid) {
-            break (x, y);
+        if rock_intersects_anything(pos + DOWN, rock, solid) {
+            break pos;
         } else {
-            y -= 1;
+            pos += DOWN;
         }
     }
 }
 
-pub(crate) fn simulate(jets: &[char], nrocks: usize) -> i64 {
-    let mut solid = HashSet::<(i64, i64)>::default();
-    let mut highest_y = -1;
-    let mut jet_index = 0;
-    let mut states = vec![];
-    let mut rock_index = 0;
-
-    let cycle = 'outer: loop {
-        let rock = &ROCKS[rock_index % ROCKS.len()];
-        let (x, y) = drop_rock(2, highest_y + 4, jets, &mut jet_index, rock, &solid);
-        let new_highest_y = insert_rock(x, y, rock, &mut solid);
-        let delta = (new_highest_y - highest_y).max(0);
-        highest_y += delta;
-
-        states.push((jet_index % jets.len(), delta));
-        let n = states.len();
-
-        for cycle in (0..states.len() / 2).step_by(ROCKS.len()).skip(1) {
-            if states[n - cycle..] == states[n - 2 * cycle..n - cycle] {
-                break 'outer cycle;
-            }
+struct Chamber<'a> {
+    jets: &'a [char],
+    jet_index: usize,
+    rock_index: usize,
+    solid: SparseGrid<char>,
+    tops: [i64; CAVE_WIDTH as usize],
+}
+
+impl<'a> Chamber<'a> {
+    fn new(jets: &'a [char]) -> Self {
+        Self {
+            jets,
+            jet_index: 0,
+            rock_index: 0,
+            solid: SparseGrid::new(),
+            tops: [-1; CAVE_WIDTH as usize],
+        }
+    }
+
+    fn height(&self) -> i64 {
+        self.solid.bounds().map_or(0, |[_, max]| max.y + 1)
+    }
+
+    fn drop_next(&mut self) {
+        let rock = &ROCKS[self.rock_index % ROCKS.len()];
+        let start = Point2::new(2, self.height() + 3);
+        let pos = drop_rock(start, self.jets, &mut self.jet_index, rock, &self.solid);
+        insert_rock(pos, rock, &mut self.solid);
+
+        for p in rock_cells(pos, rock) {
+            let top = &mut self.tops[p.x as usize];
+            *top = (*top).max(p.y);
         }
 
-        rock_index += 1;
-    };
+        self.jet_index %= self.jets.len();
+        self.rock_index += 1;
+    }
 
-    let ncycles = nrocks / cycle;
-    let cycle_height = states[states.len() - cycle..]
-        .iter()
-        .map(|(_, delta)| delta)
-        .sum::<i64>();
+    /// Next rock, next jet and the shape of the surface. Two chambers with
+    /// the same key behave the same from here on.
+    fn key(&self) -> (usize, usize, [i64; CAVE_WIDTH as usize]) {
+        let height = self.height();
+        let skyline = self.tops.map(|top| height - top);
 
-    let remainder = nrocks % cycle;
-    let remainder_height = states[..remainder]
-        .iter()
-        .map(|(_, delta)| delta)
-        .sum::<i64>();
+        (self.rock_index % ROCKS.len(), self.jet_index, skyline)
+    }
+}
 
-    ncycles as i64 * cycle_height + remainder_height
+pub fn simulate(jets: &[char], nrocks: usize) -> i64 {
+    extrapolate(
+        Chamber::new(jets),
+        nrocks,
+        Chamber::drop_next,
+        Chamber::key,
+        Chamber::height,
+    )
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let jets = lines[0].chars().collect_vec();
-    let height = simulate(&jets, 2022);
-    println!("part A: {:?}", height);
+pub struct Day17;
+
+impl Puzzle for Day17 {
+    type Input = Vec<char>;
 
-    let height = simulate(&jets, 1000000000000);
-    println!("part B: {:?}", height);
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        let line = *lines(input).first().context("input is empty")?;
+
+        at_line(
+            0,
+            line,
+            parse_chars(line, |c| match c {
+                '<' | '>' => Ok(c),
+                _ => bail!("expected a jet"),
+            }),
+        )
+    }
+
+    fn part_a(jets: &Self::Input) -> Result<Answer> {
+        Ok(simulate(jets, 2022).into())
+    }
+
+    fn part_b(jets: &Self::Input) -> Result<Answer> {
+        Ok(simulate(jets, 1000000000000).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day17.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day17::parse(EXAMPLE).unwrap();
+        assert_eq!(Day17::part_a(&input).unwrap(), 3068.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day17::parse(EXAMPLE).unwrap();
+        assert_eq!(Day17::part_b(&input).unwrap(), 1514285714288i64.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day17>(17);
     }
 }
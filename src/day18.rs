@@ -1,130 +1,98 @@
 use crate::common::*;
-use recap::Recap;
-use serde::Deserialize;
-
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Recap, Deserialize)]
-#[recap(regex = r#"(?P<x>[0-9]+),(?P<y>[0-9]+),(?P<z>[0-9]+)"#)]
-struct Cube {
-    x: i64,
-    y: i64,
-    z: i64,
-}
 
-const FACES: [[i64; 3]; 6] = [
-    [1, 0, 0],
-    [-1, 0, 0],
-    [0, 1, 0],
-    [0, -1, 0],
-    [0, 0, 1],
-    [0, 0, -1],
-];
-
-fn count_faces(cubes: &[Cube]) -> i64 {
-    let mut total = 0;
-    let cubes = HashSet::from_iter(cubes);
-
-    for cube in &cubes {
-        for [dx, dy, dz] in FACES {
-            if !cubes.contains(&Cube {
-                x: cube.x + dx,
-                y: cube.y + dy,
-                z: cube.z + dz,
-            }) {
-                total += 1;
-            }
-        }
+pub type Cube = Point3<i64>;
+
+pub fn parse_cube(line: &str) -> Result<Cube> {
+    match parse_list(line, ',')?[..] {
+        [x, y, z] => Ok(Cube::new(x, y, z)),
+        _ => bail!("expected three coordinates: {:?}", line),
     }
+}
 
-    total
+pub fn count_faces(cubes: &[Cube]) -> usize {
+    let cubes = HashSet::from_iter(cubes.iter().copied());
+
+    cubes
+        .iter()
+        .flat_map(|cube| cube.neighbors6())
+        .filter(|p| !cubes.contains(p))
+        .count()
 }
 
-fn count_exterior_faces(cubes: &[Cube]) -> i64 {
-    let cubes = HashSet::from_iter(cubes);
+pub fn count_exterior_faces(cubes: &[Cube]) -> usize {
+    let cubes = HashSet::from_iter(cubes.iter().copied());
 
-    let (mut x0, mut x1) = (0, 0);
-    let (mut y0, mut y1) = (0, 0);
-    let (mut z0, mut z1) = (0, 0);
+    let mut min = Cube::default();
+    let mut max = Cube::default();
 
     for &cube in &cubes {
-        x0 = x0.min(cube.x - 1);
-        y0 = y0.min(cube.y - 1);
-        z0 = z0.min(cube.z - 1);
-
-        x1 = x1.max(cube.x + 1);
-        y1 = y1.max(cube.y + 1);
-        z1 = z1.max(cube.z + 1);
+        min = Cube::new(min.x.min(cube.x), min.y.min(cube.y), min.z.min(cube.z));
+        max = Cube::new(max.x.max(cube.x), max.y.max(cube.y), max.z.max(cube.z));
     }
 
-    let mut queue = vec![];
-    queue.push(Cube {
-        x: x0,
-        y: y0,
-        z: z0,
-    });
-
-    let mut exterior = HashSet::<Cube>::default();
-    exterior.insert(queue[0]);
-
-    while let Some(Cube { x, y, z }) = queue.pop() {
-        for [dx, dy, dz] in FACES {
-            let p = Cube {
-                x: x + dx,
-                y: y + dy,
-                z: z + dz,
-            };
-
-            if (x0..=x1).contains(&p.x)
-                && (y0..=y1).contains(&p.y)
-                && (z0..=z1).contains(&p.z)
-                && !cubes.contains(&p)
-                && exterior.insert(p)
-            {
-                queue.push(p);
-            }
-        }
-    }
+    // Leave a layer of air around the droplet so the flood fill can reach all sides.
+    let one = Cube::new(1, 1, 1);
+    let (min, max) = (min - one, max + one);
 
-    let mut total = 0;
-    let cubes = HashSet::from_iter(cubes);
-
-    for cube in &cubes {
-        for [dx, dy, dz] in FACES {
-            if exterior.contains(&Cube {
-                x: cube.x + dx,
-                y: cube.y + dy,
-                z: cube.z + dz,
-            }) {
-                total += 1;
-            }
-        }
-    }
+    let inside = |p: &Cube| {
+        (min.x..=max.x).contains(&p.x)
+            && (min.y..=max.y).contains(&p.y)
+            && (min.z..=max.z).contains(&p.z)
+    };
 
-    total
-}
+    let successors = |cube: &Cube| {
+        cube.neighbors6()
+            .filter(|p| inside(p) && !cubes.contains(p))
+            .collect_vec()
+    };
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let cubes = lines
+    let exterior = bfs([min], successors, |_| false).dists;
+
+    cubes
         .iter()
-        .map(|l| l.parse::<Cube>().unwrap())
-        .collect_vec();
+        .flat_map(|cube| cube.neighbors6())
+        .filter(|p| exterior.contains_key(p))
+        .count()
+}
 
-    println!("part A: {}", count_faces(&cubes));
-    println!("part B: {}", count_exterior_faces(&cubes));
+pub struct Day18;
 
-    Ok(())
+impl Puzzle for Day18 {
+    type Input = Vec<Cube>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(&lines(input), parse_cube)
+    }
+
+    fn part_a(cubes: &Self::Input) -> Result<Answer> {
+        Ok(count_faces(cubes).into())
+    }
+
+    fn part_b(cubes: &Self::Input) -> Result<Answer> {
+        Ok(count_exterior_faces(cubes).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day18.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day18::parse(EXAMPLE).unwrap();
+        assert_eq!(Day18::part_a(&input).unwrap(), 64.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day18::parse(EXAMPLE).unwrap();
+        assert_eq!(Day18::part_b(&input).unwrap(), 58.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day18>(18);
     }
 }
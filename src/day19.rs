@@ -3,10 +3,10 @@ use recap::Recap;
 use serde::Deserialize;
 use std::str::FromStr;
 
-const ORE: usize = 0;
-const CLAY: usize = 1;
-const OBSIDIAN: usize = 2;
-const GEODE: usize = 3;
+pub const ORE: usize = 0;
+pub const CLAY: usize = 1;
+pub const OBSIDIAN: usize = 2;
+pub const GEODE: usize = 3;
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug, Recap, Deserialize)]
 #[recap(
@@ -23,9 +23,9 @@ struct RawBlueprint {
 }
 
 #[derive(Default, Debug)]
-struct Blueprint {
-    id: i32,
-    robots: [[i32; 4]; 4],
+pub struct Blueprint {
+    pub id: i32,
+    pub robots: [[i32; 4]; 4],
 }
 
 impl FromStr for Blueprint {
@@ -33,9 +33,11 @@ impl FromStr for Blueprint {
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let input = s.parse::<RawBlueprint>()?;
-        let mut result = Blueprint::default();
+        let mut result = Blueprint {
+            id: input.id,
+            ..default()
+        };
 
-        result.id = input.id;
         result.robots[ORE][ORE] = input.ore_robot_ore_required;
         result.robots[CLAY][ORE] = input.clay_robot_ore_required;
         result.robots[OBSIDIAN][ORE] = input.obsidian_robot_ore_required;
@@ -53,7 +55,7 @@ struct State {
     robots: [i32; 4],
 }
 
-fn simulate(blueprint: &Blueprint, max_time: i32) -> i32 {
+pub fn simulate(blueprint: &Blueprint, max_time: i32) -> i32 {
     let mut queue = vec![State {
         time: 0,
         resources: [0, 0, 0, 0],
@@ -61,19 +63,29 @@ fn simulate(blueprint: &Blueprint, max_time: i32) -> i32 {
     }];
 
     let mut max_bots = [0; 4];
-    for i in 0..4 {
+    for (i, max_bot) in enumerate(&mut max_bots) {
         for j in 0..4 {
-            max_bots[i] = max_bots[i].max(blueprint.robots[j][i]);
+            *max_bot = (*max_bot).max(blueprint.robots[j][i]);
         }
     }
     max_bots[GEODE] = i32::MAX;
 
     let mut seen = HashSet::default();
     seen.insert(queue[0]);
+    let mut best = 0;
 
     while let Some(state) = queue.pop() {
-        for i in 0..4 {
-            if state.robots[i] >= max_bots[i] {
+        let remaining = max_time - state.time;
+        let geodes = state.resources[GEODE] + state.robots[GEODE] * remaining;
+        best = best.max(geodes);
+
+        // Even building a geode robot every remaining minute cannot beat `best`
+        if geodes + remaining * (remaining - 1) / 2 <= best {
+            continue;
+        }
+
+        for (i, &max_bot) in enumerate(&max_bots) {
+            if state.robots[i] >= max_bot {
                 continue;
             }
 
@@ -109,37 +121,46 @@ fn simulate(blueprint: &Blueprint, max_time: i32) -> i32 {
         }
     }
 
-    seen.iter()
-        .map(|s| s.resources[GEODE] + s.robots[GEODE] * (max_time - s.time))
-        .max()
-        .unwrap()
+    best
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let blueprints = lines
-        .iter()
-        .map(|l| l.parse::<Blueprint>().unwrap())
-        .collect_vec();
-
-    let max = blueprints
-        .iter()
-        .map(|b| simulate(b, 24) * b.id)
-        .sum::<i32>();
-    println!("part A: {:?}", max);
-
-    let result = blueprints[..3]
-        .iter()
-        .map(|b| simulate(b, 32) as i64)
-        .product::<i64>();
-    println!("part B: {:?}", result);
-
-    Ok(())
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    type Input = Vec<Blueprint>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(&lines(input), |line| {
+            line.parse::<Blueprint>().context("expected a blueprint")
+        })
+    }
+
+    fn part_a(blueprints: &Self::Input) -> Result<Answer> {
+        let max = blueprints
+            .iter()
+            .map(|b| simulate(b, 24) * b.id)
+            .sum::<i32>();
+
+        Ok(max.into())
+    }
+
+    fn part_b(blueprints: &Self::Input) -> Result<Answer> {
+        let result = blueprints
+            .iter()
+            .take(3)
+            .map(|b| simulate(b, 32) as i64)
+            .product::<i64>();
+
+        Ok(result.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day19.example");
+
     #[test]
     fn test_a() {
         let mut b = Blueprint::default();
@@ -159,10 +180,19 @@ mod tests {
         b.robots[GEODE][ORE] = 3;
         b.robots[GEODE][OBSIDIAN] = 12;
         assert_eq!(simulate(&b, 24), 12);
+
+        let input = Day19::parse(EXAMPLE).unwrap();
+        assert_eq!(Day19::part_a(&input).unwrap(), 33.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day19::parse(EXAMPLE).unwrap();
+        assert_eq!(Day19::part_b(&input).unwrap(), 3472i64.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day19>(19);
     }
 }
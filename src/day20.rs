@@ -1,13 +1,12 @@
 use crate::common::*;
 
-fn mix_numbers(numbers: &[i64], times: usize) -> Vec<i64> {
+pub fn mix_numbers(numbers: &[i64], times: usize) -> Vec<i64> {
     let n = numbers.len();
     let mut nums = numbers.to_vec();
     let mut pos = (0..n).collect_vec();
 
     for _ in 0..times {
-        for i in 0..n {
-            let delta = numbers[i];
+        for (i, &delta) in enumerate(numbers) {
             let old_index = pos.iter().position(|&p| p == i).unwrap();
             let mut new_index = old_index
                 + if delta >= 0 {
@@ -33,7 +32,7 @@ fn mix_numbers(numbers: &[i64], times: usize) -> Vec<i64> {
     nums
 }
 
-fn find_thousands(nums: &[i64]) -> [i64; 3] {
+pub fn find_thousands(nums: &[i64]) -> [i64; 3] {
     let n = nums.len();
     let index = nums.iter().position(|&v| v == 0).unwrap();
 
@@ -44,27 +43,34 @@ fn find_thousands(nums: &[i64]) -> [i64; 3] {
     ]
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let numbers = lines
-        .iter()
-        .map(|x| x.parse::<i64>())
-        .collect::<Result<Vec<_>, _>>()?;
+pub struct Day20;
 
-    let [a, b, c] = find_thousands(&mix_numbers(&numbers, 1));
-    println!("part A: {}", a + b + c);
+impl Puzzle for Day20 {
+    type Input = Vec<i64>;
 
-    let new_numbers = numbers.iter().map(|v| v * 811589153).collect_vec();
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(&lines(input), |line| Ok(line.parse()?))
+    }
+
+    fn part_a(numbers: &Self::Input) -> Result<Answer> {
+        let [a, b, c] = find_thousands(&mix_numbers(numbers, 1));
+        Ok((a + b + c).into())
+    }
 
-    let [a, b, c] = find_thousands(&mix_numbers(&new_numbers, 10));
-    println!("part A: {}", a + b + c);
+    fn part_b(numbers: &Self::Input) -> Result<Answer> {
+        let new_numbers = numbers.iter().map(|v| v * 811589153).collect_vec();
 
-    Ok(())
+        let [a, b, c] = find_thousands(&mix_numbers(&new_numbers, 10));
+        Ok((a + b + c).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day20.example");
+
     #[test]
     fn test_a() {
         let nums = vec![1, 2, -3, 3, -2, 0, 4];
@@ -72,10 +78,19 @@ mod tests {
         assert_eq!(mix_numbers(&nums, 1), expected);
 
         assert_eq!(find_thousands(&expected), [4, -3, 2]);
+
+        let input = Day20::parse(EXAMPLE).unwrap();
+        assert_eq!(Day20::part_a(&input).unwrap(), 3.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day20::parse(EXAMPLE).unwrap();
+        assert_eq!(Day20::part_b(&input).unwrap(), 1623178306i64.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day20>(20);
     }
 }
@@ -1,9 +1,9 @@
 use crate::common::*;
 
-type Val = i64;
+pub type Val = i64;
 
 #[derive(Debug, Clone, Copy)]
-enum BinOp {
+pub enum BinOp {
     Add,
     Sub,
     Mul,
@@ -11,42 +11,39 @@ enum BinOp {
 }
 
 #[derive(Debug, Clone)]
-enum Expr {
+pub enum Expr {
     Constant(Val),
     Binary(String, BinOp, String),
 }
 
-fn parse_expressions(lines: Lines) -> HashMap<String, Expr> {
-    let mut result = HashMap::default();
-
-    for line in lines {
-        let mut parts = line.splitn(2, ": ");
-        let name = parts.next().unwrap();
-        let expr = parts.next().unwrap();
-
-        let expr = if let Some(matches) = find_regex("[0-9]+", expr) {
-            Expr::Constant(matches[0].parse().unwrap())
-        } else if let Some(matches) = find_regex("([a-z]{4}) ([+-/*]) ([a-z]{4})", expr) {
-            let op = match &matches[2] {
-                "+" => BinOp::Add,
-                "-" => BinOp::Sub,
-                "/" => BinOp::Div,
-                "*" => BinOp::Mul,
-                _ => unreachable!(),
-            };
-
-            Expr::Binary(matches[1].to_string(), op, matches[3].to_string())
-        } else {
-            panic!("invalid line: {}", line);
+pub fn parse_expression(line: &str) -> Result<(String, Expr)> {
+    let (name, expr) = line.split_once(": ").context("expected a monkey name")?;
+
+    let expr = if let Some(matches) = regex!("^[0-9]+$").captures(expr) {
+        Expr::Constant(matches[0].parse()?)
+    } else if let Some(matches) = regex!("^([a-z]{4}) ([+-/*]) ([a-z]{4})$").captures(expr) {
+        let op = match &matches[2] {
+            "+" => BinOp::Add,
+            "-" => BinOp::Sub,
+            "/" => BinOp::Div,
+            "*" => BinOp::Mul,
+            _ => bail!("unknown operator"),
         };
 
-        result.insert(name.to_string(), expr);
-    }
+        Expr::Binary(matches[1].to_string(), op, matches[3].to_string())
+    } else {
+        let column = name.len() + ": ".len();
+        return Err(error_at(line, column, "expected a number or an operation"));
+    };
+
+    Ok((name.to_string(), expr))
+}
 
-    result
+pub fn parse_expressions(lines: Lines) -> Result<HashMap<String, Expr>> {
+    Ok(parse_lines(lines, parse_expression)?.into_iter().collect())
 }
 
-fn evaluate(key: &str, monkeys: &HashMap<String, Expr>) -> Option<Val> {
+pub fn evaluate(key: &str, monkeys: &HashMap<String, Expr>) -> Option<Val> {
     Some(match monkeys.get(key)? {
         Expr::Constant(v) => *v,
         Expr::Binary(a, op, b) => {
@@ -63,14 +60,14 @@ fn evaluate(key: &str, monkeys: &HashMap<String, Expr>) -> Option<Val> {
     })
 }
 
-fn solve<'a>(mut var: &'a str, val: Val, monkeys: &'a HashMap<String, Expr>) -> (&'a str, Val) {
+pub fn solve<'a>(var: &'a str, val: Val, monkeys: &'a HashMap<String, Expr>) -> (&'a str, Val) {
     let Some(expr) = monkeys.get(var) else {
         return (var, val);
     };
 
     let Expr::Binary(lhs, op, rhs) = expr else {
-            panic!("invalid expression");
-        };
+        panic!("invalid expression");
+    };
 
     match (evaluate(lhs, monkeys), op, evaluate(rhs, monkeys)) {
         (Some(lhs), BinOp::Add, None) => solve(rhs, val - lhs, monkeys),
@@ -87,39 +84,60 @@ fn solve<'a>(mut var: &'a str, val: Val, monkeys: &'a HashMap<String, Expr>) ->
     }
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let mut monkeys = parse_expressions(lines);
-    println!("part A: {:?}", evaluate("root", &monkeys));
-
-    // Remove human monkey
-    monkeys.remove("humn");
-
-    // Solve `var == constant` or `constant == var` for root
-    let answer = match &monkeys["root"] {
-        Expr::Binary(lhs, _, rhs) => match (evaluate(lhs, &monkeys), evaluate(rhs, &monkeys)) {
-            (Some(lhs), None) => solve(rhs, lhs, &monkeys),
-            (None, Some(rhs)) => solve(lhs, rhs, &monkeys),
-            _ => unreachable!(),
-        },
-        Expr::Constant(_) => unreachable!(),
-    };
+pub struct Day21;
 
-    println!("part B: {:?}", answer);
+impl Puzzle for Day21 {
+    type Input = HashMap<String, Expr>;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_expressions(&lines(input))
+    }
+
+    fn part_a(monkeys: &Self::Input) -> Result<Answer> {
+        let answer = evaluate("root", monkeys).context("cannot evaluate root")?;
+        Ok(answer.into())
+    }
+
+    fn part_b(monkeys: &Self::Input) -> Result<Answer> {
+        let mut monkeys = monkeys.clone();
+
+        // Remove human monkey
+        monkeys.remove("humn");
+
+        // Solve `var == constant` or `constant == var` for root
+        let (_, answer) = match &monkeys["root"] {
+            Expr::Binary(lhs, _, rhs) => match (evaluate(lhs, &monkeys), evaluate(rhs, &monkeys)) {
+                (Some(lhs), None) => solve(rhs, lhs, &monkeys),
+                (None, Some(rhs)) => solve(lhs, rhs, &monkeys),
+                _ => unreachable!(),
+            },
+            Expr::Constant(_) => unreachable!(),
+        };
+
+        Ok(answer.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day21.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day21::parse(EXAMPLE).unwrap();
+        assert_eq!(Day21::part_a(&input).unwrap(), 152.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day21::parse(EXAMPLE).unwrap();
+        assert_eq!(Day21::part_b(&input).unwrap(), 301.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day21>(21);
     }
 }
@@ -1,137 +1,595 @@
 use crate::common::*;
-use ndarray::prelude::*;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Step {
+pub enum Step {
     TurnLeft,
     TurnRight,
-    Forward,
+    Forward(usize),
 }
 
-fn parse_steps(line: &str) -> Vec<Step> {
+pub fn parse_steps(line: &str) -> Result<Vec<Step>> {
     let mut output = vec![];
-    let mut n = 0;
+    let mut n = None;
 
-    for c in line.chars() {
-        if let Some(i) = c.to_digit(10) {
-            n = n * 10 + i;
-        } else {
-            while n > 0 {
-                output.push(Step::Forward);
-                n -= 1;
+    for (i, c) in enumerate(line.chars()) {
+        if let Some(d) = c.to_digit(10) {
+            let count = n.unwrap_or(0usize).checked_mul(10);
+            n = count.and_then(|v| v.checked_add(d as usize));
+
+            if n.is_none() {
+                return Err(error_at(line, i, "step count is too large"));
             }
+        } else {
+            output.extend(n.take().map(Step::Forward));
 
             match c {
                 'L' => output.push(Step::TurnLeft),
                 'R' => output.push(Step::TurnRight),
-                _ => panic!("invalid character: {:?}", c),
+                _ => return Err(error_at(line, i, format!("invalid step {:?}", c))),
             }
         }
     }
 
-    while n > 0 {
-        output.push(Step::Forward);
-        n -= 1;
-    }
-
-    output
+    output.extend(n.map(Step::Forward));
+    Ok(output)
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
     Empty,
     Wall,
     Open,
 }
 
-fn parse_map(lines: &[&str]) -> Array2<Tile> {
-    let height = lines.len();
-    let width = lines.iter().map(|line| line.chars().count()).max().unwrap();
+pub fn parse_map(lines: &[&str]) -> Result<Grid<Tile>> {
+    Grid::parse(lines, |c| match c {
+        ' ' => Ok(Tile::Empty),
+        '.' => Ok(Tile::Open),
+        '#' => Ok(Tile::Wall),
+        _ => bail!("expected a tile"),
+    })
+}
+
+pub type Pos = [usize; 2];
 
-    let mut grid = Array2::from_elem((height, width), Tile::Empty);
+/// Facing value used in the password: right, down, left, up.
+pub fn facing(dir: Dir4) -> usize {
+    match dir {
+        Dir4::E => 0,
+        Dir4::S => 1,
+        Dir4::W => 2,
+        Dir4::N => 3,
+    }
+}
+
+pub fn password(pos: Pos, dir: Dir4) -> usize {
+    1000 * (pos[0] + 1) + 4 * (pos[1] + 1) + facing(dir)
+}
 
-    for (i, line) in enumerate(lines) {
-        for (j, c) in enumerate(line.chars()) {
-            grid[[i, j]] = match c {
-                ' ' => Tile::Empty,
-                '.' => Tile::Open,
-                '#' => Tile::Wall,
-                c => panic!("invalid character: {:?}", c),
-            };
+/// Next tile when walking off the flat map, wrapping around to the other
+/// side of the row or column.
+pub fn wrap_flat(map: &Grid<Tile>, pos: Pos, dir: Dir4) -> (Pos, Dir4) {
+    let [height, width] = map.shape();
+    let [di, dj] = grid_delta(dir);
+    let [mut i, mut j] = pos;
+
+    loop {
+        i = (i as isize + di).rem_euclid(height as isize) as usize;
+        j = (j as isize + dj).rem_euclid(width as isize) as usize;
+
+        if map[[i, j]] != Tile::Empty {
+            break ([i, j], dir);
         }
     }
+}
 
-    grid
+/// Jump tables for walking in straight lines. Starting anywhere and walking
+/// forward without walls always ends up back at the start, so every
+/// position and facing lies on exactly one cyclic lane.
+pub struct Lanes {
+    lanes: Vec<Vec<(Pos, Dir4)>>,
+    open_ahead: Vec<Vec<Option<usize>>>,
+    index: Grid<[(usize, usize); 4]>,
 }
 
-fn walk_map(map: ArrayView2<Tile>, steps: &[Step]) -> (usize, usize, usize) {
-    let (height, width) = map.dim();
-    let mut i = 1;
-    let mut j = map.row(i).iter().position(|&t| t == Tile::Open).unwrap();
-    let mut dir = 0;
+impl Lanes {
+    /// Builds the lanes, calling `wrap` whenever the next tile is off the map.
+    pub fn new<F>(map: &Grid<Tile>, wrap: F) -> Self
+    where
+        F: Fn(Pos, Dir4) -> (Pos, Dir4),
+    {
+        let next = |pos, dir| match map.step(pos, dir) {
+            Some(p) if map[p] != Tile::Empty => (p, dir),
+            _ => wrap(pos, dir),
+        };
 
-    for &step in steps {
-        if step != Step::Forward {
-            dir = match step {
-                Step::TurnLeft => (dir + 3) % 4,
-                Step::TurnRight => (dir + 1) % 4,
-                _ => unreachable!(),
-            };
-
-            continue;
+        let mut lanes = vec![];
+        let mut open_ahead = vec![];
+        let mut index = Grid::new(map.rows(), map.cols(), [(usize::MAX, 0); 4]);
+
+        for pos in map.positions().filter(|&p| map[p] != Tile::Empty) {
+            for dir in Dir4::ALL {
+                if index[pos][facing(dir)].0 != usize::MAX {
+                    continue;
+                }
+
+                let id = lanes.len();
+                let mut lane = vec![];
+                let mut state = (pos, dir);
+
+                loop {
+                    index[state.0][facing(state.1)] = (id, lane.len());
+                    lane.push(state);
+                    state = next(state.0, state.1);
+
+                    if state == (pos, dir) {
+                        break;
+                    }
+                }
+
+                // Number of open tiles ahead of each entry before the next wall
+                let n = lane.len();
+                let mut ahead = vec![None; n];
+                let mut run = None;
+
+                for k in (0..2 * n).rev() {
+                    ahead[k % n] = run;
+                    run = match map[lane[k % n].0] {
+                        Tile::Wall => Some(0),
+                        _ => run.map(|r| r + 1),
+                    };
+                }
+
+                lanes.push(lane);
+                open_ahead.push(ahead);
+            }
+        }
+
+        Self {
+            lanes,
+            open_ahead,
+            index,
         }
+    }
 
-        let [di, dj] = match dir {
-            0 => [0, 1],
-            1 => [1, 0],
-            2 => [0, width - 1],
-            3 => [height - 1, 0],
-            _ => unreachable!(),
+    /// Takes up to `n` steps forward, stopping in front of a wall. Appends
+    /// every tile passed to `path`, if given.
+    pub fn forward(
+        &self,
+        pos: Pos,
+        dir: Dir4,
+        n: usize,
+        path: Option<&mut Vec<(Pos, Dir4)>>,
+    ) -> (Pos, Dir4) {
+        let (id, k) = self.index[pos][facing(dir)];
+        let lane = &self.lanes[id];
+        let len = lane.len();
+
+        // Without walls, only the last lap and the remainder matter
+        let steps = match self.open_ahead[id][k] {
+            Some(ahead) => n.min(ahead),
+            None if n < len => n,
+            None => len + n % len,
         };
 
-        let mut ni = i;
-        let mut nj = j;
-        let is_valid = loop {
-            ni = (ni + di) % height;
-            nj = (nj + dj) % width;
+        if let Some(path) = path {
+            path.extend((1..=steps).map(|t| lane[(k + t) % len]));
+        }
 
-            match map[[ni, nj]] {
-                Tile::Empty => continue,
-                Tile::Wall => break false,
-                Tile::Open => break true,
+        lane[(k + steps) % len]
+    }
+}
+
+/// Walks the path, calling `wrap` whenever the next tile is off the map.
+/// Appends the starting tile and every tile passed to `path`, if given.
+fn walk_with<F>(
+    map: &Grid<Tile>,
+    steps: &[Step],
+    wrap: F,
+    mut path: Option<&mut Vec<(Pos, Dir4)>>,
+) -> Result<(Pos, Dir4)>
+where
+    F: Fn(Pos, Dir4) -> (Pos, Dir4),
+{
+    let j = map
+        .row(0)
+        .iter()
+        .position(|&t| t == Tile::Open)
+        .context("no open tile on the top row")?;
+    let lanes = Lanes::new(map, wrap);
+    let mut pos = [0, j];
+    let mut dir = Dir4::E;
+
+    if let Some(path) = path.as_deref_mut() {
+        path.push((pos, dir));
+    }
+
+    for &step in steps {
+        match step {
+            Step::TurnLeft => dir = dir.turn_left(),
+            Step::TurnRight => dir = dir.turn_right(),
+            Step::Forward(n) => {
+                (pos, dir) = lanes.forward(pos, dir, n, path.as_deref_mut());
+                continue;
             }
+        }
+
+        if let Some(path) = path.as_deref_mut() {
+            path.push((pos, dir));
+        }
+    }
+
+    Ok((pos, dir))
+}
+
+/// Final position and facing after walking the path.
+pub fn walk<F>(map: &Grid<Tile>, steps: &[Step], wrap: F) -> Result<(Pos, Dir4)>
+where
+    F: Fn(Pos, Dir4) -> (Pos, Dir4),
+{
+    walk_with(map, steps, wrap, None)
+}
+
+/// Every position and facing along the path, starting with the first tile.
+pub fn trace<F>(map: &Grid<Tile>, steps: &[Step], wrap: F) -> Result<Vec<(Pos, Dir4)>>
+where
+    F: Fn(Pos, Dir4) -> (Pos, Dir4),
+{
+    let mut path = vec![];
+    walk_with(map, steps, wrap, Some(&mut path))?;
+    Ok(path)
+}
+
+/// Draws the map with the last facing on every visited tile, as in the
+/// puzzle text.
+pub fn render_path(map: &Grid<Tile>, path: &[(Pos, Dir4)]) -> Grid<char> {
+    let mut output = map.map(|t| match t {
+        Tile::Empty => ' ',
+        Tile::Open => '.',
+        Tile::Wall => '#',
+    });
+
+    for &(pos, dir) in path {
+        output[pos] = match dir {
+            Dir4::E => '>',
+            Dir4::S => 'v',
+            Dir4::W => '<',
+            Dir4::N => '^',
+        };
+    }
+
+    output
+}
+
+/// Writes the rendered path to `filename` as a PPM image.
+pub fn write_path_image(map: &Grid<Tile>, path: &[(Pos, Dir4)], filename: &str) -> Result {
+    let image = render_path(map, path).to_ppm(4, |&c| match c {
+        ' ' => [0, 0, 0],
+        '.' => [224, 224, 224],
+        '#' => [96, 96, 96],
+        _ => [220, 40, 40],
+    });
+
+    std::fs::write(filename, image).with_context(|| format!("failed to write: {}", filename))
+}
+
+/// Orientation of a face once the net is folded: the outward normal and the
+/// directions in which the columns and the rows of the map increase.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub normal: Point3<i64>,
+    pub right: Point3<i64>,
+    pub down: Point3<i64>,
+}
+
+impl Frame {
+    /// Direction on the cube of walking in `dir` on this face.
+    fn axis(&self, dir: Dir4) -> Point3<i64> {
+        match dir {
+            Dir4::E => self.right,
+            Dir4::S => self.down,
+            Dir4::W => -self.right,
+            Dir4::N => -self.down,
+        }
+    }
+
+    /// Frame of the face next to this one in direction `dir` of the net.
+    fn fold(&self, dir: Dir4) -> Self {
+        let Frame {
+            normal,
+            right,
+            down,
+        } = *self;
+
+        match dir {
+            Dir4::E => Frame {
+                normal: right,
+                right: -normal,
+                down,
+            },
+            Dir4::W => Frame {
+                normal: -right,
+                right: normal,
+                down,
+            },
+            Dir4::S => Frame {
+                normal: down,
+                right,
+                down: -normal,
+            },
+            Dir4::N => Frame {
+                normal: -down,
+                right,
+                down: normal,
+            },
+        }
+    }
+}
+
+/// The cube folded from the net on the map. Faces are keyed by their block
+/// position on the map, measured in multiples of `size`.
+#[derive(Debug, Clone)]
+pub struct Cube {
+    pub size: usize,
+    pub faces: HashMap<Pos, Frame>,
+}
+
+impl Cube {
+    pub fn fold(map: &Grid<Tile>) -> Result<Self> {
+        let area = map.iter().filter(|&&t| t != Tile::Empty).count();
+        let size = (1..).find(|s| 6 * s * s >= area).unwrap_or_default();
+        ensure!(
+            6 * size * size == area,
+            "map does not have the area of a cube"
+        );
+
+        let blocks = Grid::from_fn(map.rows() / size, map.cols() / size, |[i, j]| {
+            map[[i * size, j * size]] != Tile::Empty
+        });
+
+        let first = blocks.positions().find(|&p| blocks[p]).unwrap_or_default();
+        let initial = Frame {
+            normal: Point3::new(0, 0, -1),
+            right: Point3::new(1, 0, 0),
+            down: Point3::new(0, 1, 0),
         };
 
-        if is_valid {
-            (i, j) = (ni, nj);
+        let mut faces = HashMap::default();
+        let mut stack = vec![(first, initial)];
+
+        while let Some((block, frame)) = stack.pop() {
+            if faces.insert(block, frame).is_some() {
+                continue;
+            }
+
+            for dir in Dir4::ALL {
+                if let Some(next) = blocks.step(block, dir) {
+                    if blocks[next] && !faces.contains_key(&next) {
+                        stack.push((next, frame.fold(dir)));
+                    }
+                }
+            }
         }
+
+        let normals = faces.values().map(|f| f.normal).collect::<HashSet<_>>();
+        ensure!(
+            faces.len() == 6 && normals.len() == 6,
+            "map is not the net of a cube"
+        );
+
+        Ok(Self { size, faces })
     }
 
-    (i, j, dir)
+    /// Next tile when walking off the edge of a face onto the adjacent face.
+    pub fn wrap(&self, pos: Pos, dir: Dir4) -> (Pos, Dir4) {
+        let s = self.size;
+        let block = [pos[0] / s, pos[1] / s];
+        let [r, c] = [pos[0] % s, pos[1] % s];
+        let frame = &self.faces[&block];
+
+        // Position along the edge, measured along `along` on the cube
+        let (offset, along) = match dir {
+            Dir4::E | Dir4::W => (r, frame.down),
+            Dir4::N | Dir4::S => (c, frame.right),
+        };
+
+        let exit = frame.axis(dir);
+        let (next_block, next_frame) = self
+            .faces
+            .iter()
+            .find(|(_, f)| f.normal == exit)
+            .expect("every edge of a cube has a neighbor");
+
+        let next_dir = Dir4::ALL
+            .into_iter()
+            .find(|&d| next_frame.axis(d) == -frame.normal)
+            .expect("faces meet at a right angle");
+
+        let next_along = match next_dir {
+            Dir4::E | Dir4::W => next_frame.down,
+            Dir4::N | Dir4::S => next_frame.right,
+        };
+
+        let k = if next_along == along {
+            offset
+        } else {
+            s - 1 - offset
+        };
+
+        let [r, c] = match next_dir {
+            Dir4::E => [k, 0],
+            Dir4::W => [k, s - 1],
+            Dir4::S => [0, k],
+            Dir4::N => [s - 1, k],
+        };
+
+        ([next_block[0] * s + r, next_block[1] * s + c], next_dir)
+    }
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let n = lines.len();
-    let map = parse_map(&lines[..n - 1]);
-    let steps = parse_steps(lines[n - 1]);
+pub struct Day22;
 
-    let (i, j, dir) = walk_map(map.view(), &steps);
-    println!("part A: {:?}", 1000 * i + 4 * j + dir);
+impl Puzzle for Day22 {
+    type Input = (Grid<Tile>, Vec<Step>);
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        let lines = lines(input);
+        let n = lines
+            .iter()
+            .position(|l| l.trim().is_empty())
+            .context("expected a map followed by a blank line and a path")?;
+        ensure!(n + 2 == lines.len(), "expected a single line with the path");
+
+        let map = parse_map(&lines[..n])?;
+        let steps = at_line(n + 1, lines[n + 1], parse_steps(lines[n + 1]))?;
+
+        Ok((map, steps))
+    }
+
+    fn part_a((map, steps): &Self::Input) -> Result<Answer> {
+        let (pos, dir) = walk(map, steps, |p, d| wrap_flat(map, p, d))?;
+        Ok(password(pos, dir).into())
+    }
+
+    fn part_b((map, steps): &Self::Input) -> Result<Answer> {
+        let cube = Cube::fold(map)?;
+        let (pos, dir) = walk(map, steps, |p, d| cube.wrap(p, d))?;
+        Ok(password(pos, dir).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day22.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day22::parse(EXAMPLE).unwrap();
+        assert_eq!(Day22::part_a(&input).unwrap(), 6032.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day22::parse(EXAMPLE).unwrap();
+        assert_eq!(Day22::part_b(&input).unwrap(), 5031.into());
+    }
+
+    #[test]
+    fn test_steps() {
+        use Step::*;
+
+        let steps = parse_steps("10R5LL1000000").unwrap();
+        assert_eq!(
+            steps,
+            [
+                Forward(10),
+                TurnRight,
+                Forward(5),
+                TurnLeft,
+                TurnLeft,
+                Forward(1000000)
+            ]
+        );
+
+        let (map, _) = Day22::parse(EXAMPLE).unwrap();
+        let flat = |p, d| wrap_flat(&map, p, d);
+        assert_eq!(walk(&map, &steps[5..], flat).unwrap(), ([0, 10], Dir4::E));
+
+        // The open row loops around without hitting a wall
+        let row = [
+            Forward(2),
+            TurnRight,
+            Forward(3),
+            TurnLeft,
+            Forward(1000003),
+        ];
+        assert_eq!(walk(&map, &row, flat).unwrap(), ([3, 9], Dir4::E));
+    }
+
+    #[test]
+    fn test_render() {
+        let (map, steps) = Day22::parse(EXAMPLE).unwrap();
+        let path = trace(&map, &steps, |p, d| wrap_flat(&map, p, d)).unwrap();
+        assert_eq!(
+            render_path(&map, &path).to_string(),
+            [
+                "        >>v#    ",
+                "        .#v.    ",
+                "        #.v.    ",
+                "        ..v.    ",
+                "...#...v..v#    ",
+                ">>>v...>#.>>    ",
+                "..#v...#....    ",
+                "...>>>>v..#.    ",
+                "        ...#....",
+                "        .....#..",
+                "        .#......",
+                "        ......#.",
+            ]
+            .join("\n")
+        );
+
+        let cube = Cube::fold(&map).unwrap();
+        let path = trace(&map, &steps, |p, d| cube.wrap(p, d)).unwrap();
+        assert_eq!(
+            render_path(&map, &path).to_string(),
+            [
+                "        >>v#    ",
+                "        .#v.    ",
+                "        #.v.    ",
+                "        ..v.    ",
+                "...#..^...v#    ",
+                ".>>>>>^.#.>>    ",
+                ".^#....#....    ",
+                ".^........#.    ",
+                "        ...#..v.",
+                "        .....#v.",
+                "        .#v<<<<.",
+                "        ..v...#.",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_fold() {
+        let nets: [&[&str]; 3] = [
+            &["  # ", "### ", "  ##"],
+            &[" ##", " # ", "## ", "#  "],
+            &[" # ", "###", " # ", " # "],
+        ];
+
+        for net in nets {
+            // Blow up every block of the net to a face of 3x3 tiles
+            let rows = net
+                .iter()
+                .flat_map(|row| {
+                    let row = row.chars().map(|c| c.to_string().repeat(3)).join("");
+                    [row.clone(), row.clone(), row].map(|r| r.replace('#', "."))
+                })
+                .collect_vec();
+
+            let map = parse_map(&rows.iter().map(|r| r.as_str()).collect_vec()).unwrap();
+            let cube = Cube::fold(&map).unwrap();
+
+            for pos in map.positions().filter(|&p| map[p] != Tile::Empty) {
+                for dir in Dir4::ALL {
+                    if map.step(pos, dir).is_some_and(|p| map[p] != Tile::Empty) {
+                        continue;
+                    }
+
+                    let (next, next_dir) = cube.wrap(pos, dir);
+                    assert_ne!(map[next], Tile::Empty);
+                    assert_eq!(cube.wrap(next, next_dir.reverse()), (pos, dir.reverse()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day22>(22);
     }
 }
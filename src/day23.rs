@@ -1,22 +1,30 @@
 use crate::common::*;
 
-type Pos = [i64; 2];
+pub type Pos = Point2<i64>;
+
+pub fn parse_elves(lines: Lines) -> Result<SparseGrid<char>> {
+    let rows = parse_lines(lines, |line| {
+        parse_chars(line, |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => bail!("expected '#' or '.'"),
+        })
+    })?;
 
-fn parse_elves(lines: Lines) -> HashSet<Pos> {
-    let mut output = HashSet::default();
+    let mut output = SparseGrid::new();
 
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            if c == '#' {
-                output.insert([x as i64, y as i64]);
+    for (y, row) in enumerate(rows) {
+        for (x, elf) in enumerate(row) {
+            if elf {
+                output.insert(Pos::new(x as i64, y as i64), '#');
             }
         }
     }
 
-    output
+    Ok(output)
 }
 
-fn simulate(input: &HashSet<Pos>, rounds: usize) -> HashSet<Pos> {
+pub fn simulate(input: &SparseGrid<char>, rounds: usize) -> SparseGrid<char> {
     let mut elves = input.clone();
 
     for round in 0..rounds {
@@ -26,7 +34,7 @@ fn simulate(input: &HashSet<Pos>, rounds: usize) -> HashSet<Pos> {
     elves
 }
 
-fn simulate_convergence(input: &HashSet<Pos>) -> usize {
+pub fn simulate_convergence(input: &SparseGrid<char>) -> usize {
     let mut elves = input.clone();
 
     for round in 0..10000000 {
@@ -41,107 +49,92 @@ fn simulate_convergence(input: &HashSet<Pos>) -> usize {
     panic!("did not converge");
 }
 
-fn simulate_once(elves: &HashSet<Pos>, round: usize) -> HashSet<Pos> {
-    let dirs = ['N', 'S', 'W', 'E'];
+fn simulate_once(elves: &SparseGrid<char>, round: usize) -> SparseGrid<char> {
+    let dirs = [Dir4::N, Dir4::S, Dir4::W, Dir4::E];
     let mut proposes = HashMap::default();
     let mut occupied = HashMap::<Pos, usize>::default();
 
-    for &[x, y] in elves {
-        let mut neighbors = 0;
-
-        for dx in [-1, 0, 1] {
-            for dy in [-1, 0, 1] {
-                if elves.contains(&[x + dx, y + dy]) {
-                    neighbors += 1;
-                }
-            }
-        }
-
-        let [mut nx, mut ny] = [x, y];
+    for pos in elves.positions() {
+        let mut next = pos;
 
-        // only itself
-        if neighbors > 1 {
+        if pos.neighbors8().any(|p| elves.contains(p)) {
             for index in 0..dirs.len() {
-                let [dx, dy] = match dirs[(index + round) % dirs.len()] {
-                    'N' => [0, -1],
-                    'E' => [1, 0],
-                    'S' => [0, 1],
-                    'W' => [-1, 0],
-                    _ => unreachable!(),
-                };
-
-                if !elves.contains(&[x + dx, y + dy])
-                    && !elves.contains(&[x + dx + dy, y + dy - dx])
-                    && !elves.contains(&[x + dx - dy, y + dy + dx])
+                let d = dirs[(index + round) % dirs.len()].delta();
+
+                if !elves.contains(pos + d)
+                    && !elves.contains(pos + d + d.rotate_left())
+                    && !elves.contains(pos + d + d.rotate_right())
                 {
-                    nx += dx;
-                    ny += dy;
+                    next += d;
                     break;
                 }
             }
         }
 
-        proposes.insert([x, y], [nx, ny]);
-        *occupied.entry([nx, ny]).or_default() += 1;
+        proposes.insert(pos, next);
+        *occupied.entry(next).or_default() += 1;
     }
 
     proposes
         .into_iter()
-        .map(|([x, y], [nx, ny])| {
-            if occupied[&[nx, ny]] == 1 {
-                [nx, ny]
+        .map(|(pos, next)| {
+            if occupied[&next] == 1 {
+                (next, '#')
             } else {
-                [x, y]
+                (pos, '#')
             }
         })
         .collect()
 }
 
-fn compute_bounds(elves: &HashSet<Pos>) -> ([i64; 2], [i64; 2]) {
-    let (min_x, max_x) = elves
-        .iter()
-        .map(|&[x, _]| x)
-        .minmax()
-        .into_option()
-        .unwrap();
-
-    let (min_y, max_y) = elves
-        .iter()
-        .map(|&[_, y]| y)
-        .minmax()
-        .into_option()
-        .unwrap();
-
-    ([min_x, max_x], [min_y, max_y])
+pub fn compute_empty_tiles(elves: &SparseGrid<char>) -> i64 {
+    let size = elves.size();
+    size.x * size.y - (elves.len() as i64)
 }
 
-fn compute_empty_tiles(elves: &HashSet<Pos>) -> i64 {
-    let ([x0, x1], [y0, y1]) = compute_bounds(&elves);
-    let area = (x1 - x0 + 1) * (y1 - y0 + 1);
-    area - (elves.len() as i64)
+#[allow(dead_code)]
+fn visualize(elves: &SparseGrid<char>) {
+    println!("{}", elves.render(|c| *c.unwrap_or(&'.')));
 }
 
-#[allow(dead_code)]
-fn visualize(elves: &HashSet<Pos>) {
-    let ([min_x, max_x], [min_y, max_y]) = compute_bounds(elves);
+pub struct Day23;
 
-    for y in (min_y - 1)..=(max_y + 1) {
-        for x in (min_x - 1)..=(max_x + 1) {
-            if elves.contains(&[x, y]) {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
+impl Puzzle for Day23 {
+    type Input = SparseGrid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_elves(&lines(input))
+    }
 
-        println!();
+    fn part_a(elves: &Self::Input) -> Result<Answer> {
+        Ok(compute_empty_tiles(&simulate(elves, 10)).into())
+    }
+
+    fn part_b(elves: &Self::Input) -> Result<Answer> {
+        Ok(simulate_convergence(elves).into())
     }
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let elves = parse_elves(lines);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../inputs/day23.example");
 
-    println!("part A: {}", compute_empty_tiles(&simulate(&elves, 10)));
-    println!("part B: {}", simulate_convergence(&elves));
-    Ok(())
+    #[test]
+    fn test_a() {
+        let input = Day23::parse(EXAMPLE).unwrap();
+        assert_eq!(Day23::part_a(&input).unwrap(), 110.into());
+    }
+
+    #[test]
+    fn test_b() {
+        let input = Day23::parse(EXAMPLE).unwrap();
+        assert_eq!(Day23::part_b(&input).unwrap(), 20.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day23>(23);
+    }
 }
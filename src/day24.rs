@@ -1,134 +1,156 @@
 use crate::common::*;
+use std::iter::once;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
-struct Blizzard {
-    sx: i64,
-    sy: i64,
-    dx: i64,
-    dy: i64,
+pub struct Blizzard {
+    pub start: Point2<i64>,
+    pub dir: Point2<i64>,
 }
 
-fn parse_blizzards(lines: Lines) -> ([i64; 2], Vec<Blizzard>) {
+pub fn parse_blizzards(lines: Lines) -> Result<([usize; 2], Vec<Blizzard>)> {
+    let map = Grid::parse(lines, |c| match c {
+        '<' | '>' | '^' | 'v' | '.' | '#' => Ok(c),
+        _ => bail!("expected a valley tile"),
+    })?;
+
+    ensure!(map.rows() > 2 && map.cols() > 2, "valley is too small");
     let mut blizzards = vec![];
-    let height = lines.len() - 2;
-    let width = lines[0].chars().count() - 2;
-
-    for (y, line) in enumerate(lines) {
-        for (x, c) in enumerate(line.chars()) {
-            let [dx, dy] = match c {
-                '<' => [-1, 0],
-                '>' => [1, 0],
-                '^' => [0, -1],
-                'v' => [0, 1],
-                '.' => continue,
-                '#' => continue,
-                _ => panic!("invalid character: {:?}", c),
-            };
-
-            blizzards.push(Blizzard {
-                sx: x as i64 - 1,
-                sy: y as i64 - 1,
-                dx,
-                dy,
-            });
-        }
+
+    for ([y, x], &c) in map.indexed_iter() {
+        let dir = match c {
+            '<' => Dir4::W,
+            '>' => Dir4::E,
+            '^' => Dir4::N,
+            'v' => Dir4::S,
+            _ => continue,
+        };
+
+        blizzards.push(Blizzard {
+            start: Point2::new(x as i64 - 1, y as i64 - 1),
+            dir: dir.delta(),
+        });
     }
 
-    ([width as i64, height as i64], blizzards)
+    Ok(([map.rows() - 2, map.cols() - 2], blizzards))
+}
+
+/// Cells covered by a blizzard for every minute of the repeating cycle.
+pub fn blizzard_cycle(shape: [usize; 2], blizzards: &[Blizzard]) -> Vec<Grid<bool>> {
+    let [height, width] = shape;
+    let period = num::integer::lcm(height, width);
+
+    (0..period)
+        .map(|t| {
+            let mut blocked = Grid::new(height, width, false);
+
+            for b in blizzards {
+                let p = b.start + b.dir * t as i64;
+                let x = p.x.rem_euclid(width as i64) as usize;
+                let y = p.y.rem_euclid(height as i64) as usize;
+                blocked[[y, x]] = true;
+            }
+
+            blocked
+        })
+        .collect()
 }
 
-fn search_path(
-    start: [i64; 2],
-    goal: [i64; 2],
-    dims: [i64; 2],
+/// Earliest minute at which `goal` can be reached when stepping onto `start`
+/// in minute `time_start`, or `None` if `start` is blocked at that time.
+pub fn search_path(
+    start: [usize; 2],
+    goal: [usize; 2],
+    blocked: &[Grid<bool>],
     time_start: i64,
-    blizzards: &[Blizzard],
 ) -> Option<i64> {
-    let [width, height] = dims;
-    let mut pos = HashSet::default();
-    pos.insert(start);
-    let mut t = time_start;
-
-    loop {
-        let mut obstacles = HashSet::default();
-        for b in blizzards {
-            obstacles.insert((
-                (b.sx + t * b.dx).rem_euclid(width),
-                (b.sy + t * b.dy).rem_euclid(height),
-            ));
-        }
-
-        let mut new_pos = HashSet::default();
-        for [x, y] in pos {
-            for (dx, dy) in [(0, 0), (0, 1), (0, -1), (1, 0), (-1, 0)] {
-                let (nx, ny) = (x + dx, y + dy);
+    let period = blocked.len() as i64;
+    let phase = time_start.rem_euclid(period);
+    if blocked[phase as usize][start] {
+        return None;
+    }
 
-                if nx < 0 || ny < 0 || nx >= width || ny >= height {
-                    continue;
-                }
+    let successors = |&(pos, t): &([usize; 2], i64)| {
+        let t = (t + 1) % period;
+        let grid = &blocked[t as usize];
 
-                if !obstacles.contains(&(nx, ny)) {
-                    new_pos.insert([nx, ny]);
-                }
-            }
-        }
+        once(pos)
+            .chain(grid.neighbors4(pos))
+            .filter(move |&p| !grid[p])
+            .map(move |p| (p, t))
+    };
 
-        if let Some(_) = new_pos.get(&goal) {
-            return Some(t);
-        }
+    let search = bfs([(start, phase)], successors, |&(pos, _)| pos == goal);
+    search.goal_dist().map(|d| time_start + d as i64)
+}
 
-        if new_pos.is_empty() {
-            return None;
-        }
+/// Minute at which the expedition leaves the valley next to `goal`, when
+/// waiting next to `start` at minute `time`.
+pub fn cross_valley(
+    start: [usize; 2],
+    goal: [usize; 2],
+    blocked: &[Grid<bool>],
+    time: i64,
+) -> Option<i64> {
+    let period = blocked.len() as i64;
 
-        pos = new_pos;
-        t += 1;
-    }
+    (time + 1..=time + period)
+        .find_map(|t| search_path(start, goal, blocked, t))
+        .map(|t| t + 1)
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let (dims, blizzards) = parse_blizzards(lines);
-    let start = [0, 0];
-    let goal = [dims[0] - 1, dims[1] - 1];
+pub struct Day24;
 
-    // Walk from start to goal
-    let mut t = search_path(start, goal, dims, 0, &blizzards).unwrap() + 1;
-    println!("part A: {}", t);
+impl Puzzle for Day24 {
+    type Input = ([usize; 2], Vec<Blizzard>);
 
-    // Walk from goal back to start
-    let mut t = loop {
-        if let Some(t) = search_path(goal, start, dims, t, &blizzards) {
-            break t + 1;
-        }
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_blizzards(&lines(input))
+    }
 
-        t += 1;
-    };
+    fn part_a((dims, blizzards): &Self::Input) -> Result<Answer> {
+        let start = [0, 0];
+        let goal = [dims[0] - 1, dims[1] - 1];
+        let blocked = blizzard_cycle(*dims, blizzards);
 
-    // Walk from start back to goal
-    let t = loop {
-        if let Some(t) = search_path(start, goal, dims, t, &blizzards) {
-            break t + 1;
-        }
+        let t = cross_valley(start, goal, &blocked, 0).context("no path to the goal")?;
+        Ok(t.into())
+    }
 
-        t += 1;
-    };
+    fn part_b((dims, blizzards): &Self::Input) -> Result<Answer> {
+        let start = [0, 0];
+        let goal = [dims[0] - 1, dims[1] - 1];
+        let blocked = blizzard_cycle(*dims, blizzards);
 
-    println!("part B: {}", t);
+        // Walk from start to goal, back to start, and to the goal again
+        let mut t = 0;
+        for (from, to) in [(start, goal), (goal, start), (start, goal)] {
+            t = cross_valley(from, to, &blocked, t).context("no path to the goal")?;
+        }
 
-    Ok(())
+        Ok(t.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day24.example");
+
     #[test]
     fn test_a() {
-        //
+        let input = Day24::parse(EXAMPLE).unwrap();
+        assert_eq!(Day24::part_a(&input).unwrap(), 18.into());
     }
 
     #[test]
     fn test_b() {
-        //
+        let input = Day24::parse(EXAMPLE).unwrap();
+        assert_eq!(Day24::part_b(&input).unwrap(), 54.into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day24>(24);
     }
 }
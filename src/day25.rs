@@ -1,9 +1,9 @@
 use crate::common::*;
 
-fn parse_snafu(input: &str) -> i64 {
+pub fn parse_snafu(input: &str) -> Result<i64> {
     let mut output = 0;
 
-    for c in input.chars() {
+    for (i, c) in enumerate(input.chars()) {
         output *= 5;
         output += match c {
             '2' => 2,
@@ -11,21 +11,21 @@ fn parse_snafu(input: &str) -> i64 {
             '0' => 0,
             '-' => -1,
             '=' => -2,
-            _ => panic!("invalid character: {:?}", c),
+            _ => return Err(error_at(input, i, format!("invalid SNAFU digit {:?}", c))),
         };
     }
 
-    output
+    Ok(output)
 }
 
-fn generate_snafu(mut input: i64) -> String {
+pub fn generate_snafu(mut input: i64) -> String {
     let mut output = String::new();
     let mut positions = 0;
     let mut power = 1;
 
     loop {
         let x = ((input as f64) / (power as f64)).round() as i64;
-        if x >= -2 && x <= 2 {
+        if (-2..=2).contains(&x) {
             break;
         }
 
@@ -51,34 +51,49 @@ fn generate_snafu(mut input: i64) -> String {
     output
 }
 
-pub(crate) fn run(lines: Lines) -> Result {
-    let numbers = lines.iter().map(|l| parse_snafu(l)).collect_vec();
+pub struct Day25;
 
-    let total = numbers.iter().sum::<i64>();
+impl Puzzle for Day25 {
+    type Input = Vec<i64>;
 
-    println!("part A: {:?}", generate_snafu(total));
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(&lines(input), parse_snafu)
+    }
 
-    Ok(())
+    fn part_a(numbers: &Self::Input) -> Result<Answer> {
+        let total = numbers.iter().sum::<i64>();
+        Ok(generate_snafu(total).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../inputs/day25.example");
+
     #[test]
     fn test_a() {
-        assert_eq!(parse_snafu("2=-01"), 976);
-        assert_eq!(parse_snafu("1-0---0"), 12345);
-        assert_eq!(parse_snafu("1121-1110-1=0"), 314159265);
+        assert_eq!(parse_snafu("2=-01").unwrap(), 976);
+        assert_eq!(parse_snafu("1-0---0").unwrap(), 12345);
+        assert_eq!(parse_snafu("1121-1110-1=0").unwrap(), 314159265);
 
         for i in 1..100 {
             assert_eq!(
-                parse_snafu(&generate_snafu(i)),
+                parse_snafu(&generate_snafu(i)).unwrap(),
                 i,
                 "invalid snafu number {}: {}",
                 i,
                 generate_snafu(i)
             );
         }
+
+        let input = Day25::parse(EXAMPLE).unwrap();
+        assert_eq!(Day25::part_a(&input).unwrap(), "2=-1=0".into());
+    }
+
+    #[test]
+    fn test_input() {
+        check_input::<Day25>(25);
     }
 }
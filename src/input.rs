@@ -0,0 +1,93 @@
+use advent2022::common::*;
+use std::fs::read_to_string;
+use std::io::{self, Read};
+use std::path::Path;
+
+const PREFIXES: [&str; 4] = [".", "..", "inputs", "../inputs"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) enum Source {
+    #[default]
+    Default,
+    Example,
+    Path(String),
+    Stdin,
+}
+
+pub(crate) struct Input {
+    pub(crate) path: String,
+    pub(crate) content: String,
+}
+
+pub(crate) fn find_file(name: &str) -> Result<String> {
+    let candidates = PREFIXES
+        .iter()
+        .map(|prefix| format!("{}/{}", prefix, name))
+        .collect_vec();
+
+    match candidates.iter().find(|path| Path::new(path).exists()) {
+        Some(path) => Ok(path.clone()),
+        None => bail!(
+            "could not find {:?}, tried: {}",
+            name,
+            candidates.join(", ")
+        ),
+    }
+}
+
+fn normalize(content: String) -> String {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+
+    if content.contains('\r') {
+        content.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        content.to_string()
+    }
+}
+
+pub(crate) fn load(day: usize, source: &Source) -> Result<Input> {
+    let path = match source {
+        Source::Default => find_file(&format!("day{:02}", day))?,
+        Source::Example => find_file(&format!("day{:02}.example", day))?,
+        Source::Path(path) => path.clone(),
+        Source::Stdin => {
+            let mut content = String::new();
+            io::stdin()
+                .read_to_string(&mut content)
+                .context("failed to read from stdin")?;
+
+            return Ok(Input {
+                path: "<stdin>".to_string(),
+                content: normalize(content),
+            });
+        }
+    };
+
+    let content = read_to_string(&path).with_context(|| format!("failed to open: {}", path))?;
+    Ok(Input {
+        path,
+        content: normalize(content),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\r\n  b \r\n".to_string()), "a\n  b \n");
+        assert_eq!(normalize("a\rb".to_string()), "a\nb");
+        assert_eq!(normalize("\u{feff}a\n".to_string()), "a\n");
+        assert_eq!(normalize("    ...#\n".to_string()), "    ...#\n");
+    }
+
+    #[test]
+    fn test_find_file() {
+        let err = find_file("day99").unwrap_err().to_string();
+
+        for prefix in PREFIXES {
+            assert!(err.contains(&format!("{}/day99", prefix)), "{}", err);
+        }
+    }
+}
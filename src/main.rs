@@ -1,91 +1,547 @@
-mod common;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-
-use common::*;
+mod bench;
+mod input;
+mod output;
+
+use advent2022::common::*;
+use advent2022::*;
+use input::{Input, Source};
+use output::Format;
+use std::any::Any;
+use std::cell::Cell;
 use std::env;
 use std::fs::read_to_string;
-use std::path::Path;
+use std::hash::Hasher;
+use std::panic::{catch_unwind, set_hook, take_hook, UnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-fn main() -> Result {
-    let funs = [
-        day01::run,
-        day02::run,
-        day03::run,
-        day04::run,
-        day05::run,
-        day06::run,
-        day07::run,
-        day08::run,
-        day09::run,
-        day10::run,
-        day11::run,
-        day12::run,
-        day13::run,
-        day14::run,
-        day15::run,
-        day16::run,
-        day17::run,
-        day18::run,
-        day19::run,
-        day20::run,
-        day21::run,
-        day22::run,
-        day23::run,
-        day24::run,
-        day25::run,
-    ];
+type Solver = fn(&Input) -> Result<(Solution, Timings)>;
 
-    let mut args = env::args();
-    let binary = args.next().unwrap_or_default();
-    let day = args.next().unwrap_or_default();
+const DAYS: [Solver; 25] = [
+    solve::<day01::Day01>,
+    solve::<day02::Day02>,
+    solve::<day03::Day03>,
+    solve::<day04::Day04>,
+    solve::<day05::Day05>,
+    solve::<day06::Day06>,
+    solve::<day07::Day07>,
+    solve::<day08::Day08>,
+    solve::<day09::Day09>,
+    solve::<day10::Day10>,
+    solve::<day11::Day11>,
+    solve::<day12::Day12>,
+    solve::<day13::Day13>,
+    solve::<day14::Day14>,
+    solve::<day15::Day15>,
+    solve::<day16::Day16>,
+    solve::<day17::Day17>,
+    solve::<day18::Day18>,
+    solve::<day19::Day19>,
+    solve::<day20::Day20>,
+    solve::<day21::Day21>,
+    solve::<day22::Day22>,
+    solve::<day23::Day23>,
+    solve::<day24::Day24>,
+    solve::<day25::Day25>,
+];
+
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct Timings {
+    parse: Duration,
+    part_a: Duration,
+    part_b: Duration,
+}
+
+pub(crate) struct Report {
+    day: usize,
+    input_path: String,
+    input_hash: Option<u64>,
+    duration: Duration,
+    timings: Timings,
+    status: Result<Solution, String>,
+    verdicts: [Verdict; 2],
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Run,
+    Bench(bench::Settings),
+}
+
+struct Options {
+    command: Command,
+    days: Vec<usize>,
+    format: Format,
+    source: Source,
+    jobs: usize,
+}
+
+const USAGE: &str = "[bench [--runs N] [--warmup N] [--save FILE] [--compare FILE]] \
+                     [--format text|json|csv] [--input FILE | --input - | --example] [--jobs N] \
+                     [all | DAY | FIRST-LAST | DAY,DAY,...]";
+
+fn parse_day(input: &str) -> Result<usize> {
+    match input.trim().parse::<usize>() {
+        Ok(day) if (1..=DAYS.len()).contains(&day) => Ok(day),
+        _ => bail!(
+            "day must be a number between 1 and {}, found {:?}",
+            DAYS.len(),
+            input
+        ),
+    }
+}
+
+fn parse_selection(input: &str) -> Result<Vec<usize>> {
+    if input == "all" {
+        return Ok((1..=DAYS.len()).collect());
+    }
+
+    let mut days = vec![];
+
+    for part in input.split(',') {
+        let (first, last) = part.split_once('-').unwrap_or((part, part));
+        let (first, last) = (parse_day(first)?, parse_day(last)?);
+
+        if first > last {
+            bail!("invalid range of days: {:?}", part);
+        }
+
+        days.extend(first..=last);
+    }
+
+    Ok(days)
+}
+
+fn read_expected() -> Result<Expected> {
+    let Ok(path) = input::find_file("answers") else {
+        return Ok(Expected::default());
+    };
+
+    let content = read_to_string(&path).with_context(|| format!("failed to open: {}", path))?;
+    Expected::parse(&content).with_context(|| format!("failed to parse: {}", path))
+}
+
+fn hash_input(content: &str) -> u64 {
+    let mut hasher = fnv::FnvHasher::default();
+    hasher.write(content.as_bytes());
+    hasher.finish()
+}
+
+fn solve<P: Puzzle>(input: &Input) -> Result<(Solution, Timings)> {
+    let before = Instant::now();
+    let input =
+        P::parse(&input.content).with_context(|| format!("failed to parse {}", input.path))?;
+    let parse = before.elapsed();
+
+    let before = Instant::now();
+    let part_a = P::part_a(&input).context("failed to solve part A")?;
+    let part_a_time = before.elapsed();
+
+    let before = Instant::now();
+    let part_b = P::part_b(&input).context("failed to solve part B")?;
+    let part_b_time = before.elapsed();
 
-    let day = if let Ok(i) = day.parse::<usize>() {
-        i
+    let timings = Timings {
+        parse,
+        part_a: part_a_time,
+        part_b: part_b_time,
+    };
+
+    Ok((Solution::new(part_a, part_b), timings))
+}
+
+fn run_day(day: usize, input: &Input) -> Result<(Solution, Timings)> {
+    (DAYS[day - 1])(input)
+}
+
+fn time_phases(day: usize, input: &Input) -> Result<Vec<(&'static str, Duration)>> {
+    let (_, timings) = run_day(day, input)?;
+
+    Ok(vec![
+        ("parse", timings.parse),
+        ("part_a", timings.part_a),
+        ("part_b", timings.part_b),
+    ])
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
     } else {
-        bail!("usage: {} [day]", binary);
+        "unknown panic"
+    }
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs a panic hook that stays silent for panics caught by
+/// `catch_quietly` and defers to the previous hook for all others.
+fn install_panic_hook() {
+    let hook = take_hook();
+    set_hook(Box::new(move |info| {
+        if !QUIET.get() {
+            hook(info);
+        }
+    }));
+}
+
+/// Like `catch_unwind`, but a panic in `fun` is not printed by the panic
+/// hook, since it is reported along with the day instead.
+fn catch_quietly<R>(fun: impl FnOnce() -> R + UnwindSafe) -> thread::Result<R> {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            QUIET.set(self.0);
+        }
+    }
+
+    let _reset = Reset(QUIET.replace(true));
+    catch_unwind(fun)
+}
+
+fn run_report(day: usize, source: &Source, expected: &Expected) -> Report {
+    let mut input_path = String::new();
+    let mut input_hash = None;
+    let mut timings = Timings::default();
+
+    let before = Instant::now();
+    let status = match input::load(day, source) {
+        Ok(input) => {
+            input_path = input.path.clone();
+            input_hash = Some(hash_input(&input.content));
+
+            match catch_quietly(|| run_day(day, &input)) {
+                Ok(Ok((solution, t))) => {
+                    timings = t;
+                    Ok(solution)
+                }
+                Ok(Err(e)) => Err(format!("error: {:#}", e)),
+                Err(payload) => Err(format!("panic: {}", panic_message(&*payload))),
+            }
+        }
+        Err(e) => Err(format!("error: {:#}", e)),
     };
+    let duration = before.elapsed();
+
+    let verdicts = match &status {
+        Ok(solution) if *source == Source::Default => [
+            expected.verify(day, 'A', &solution.part_a),
+            expected.verify(day, 'B', &solution.part_b),
+        ],
+        _ => [Verdict::Unknown; 2],
+    };
+
+    Report {
+        day,
+        input_path,
+        input_hash,
+        duration,
+        timings,
+        status,
+        verdicts,
+    }
+}
+
+/// Runs the days on `jobs` worker threads and passes the reports to `emit` in the
+/// order of `days`, as soon as all earlier days have finished.
+fn run_reports(
+    days: &[usize],
+    source: &Source,
+    expected: &Expected,
+    jobs: usize,
+    mut emit: impl FnMut(Report),
+) {
+    let next = &AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(days.len()) {
+            let sender = sender.clone();
+
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&day) = days.get(index) else {
+                    break;
+                };
 
-    if day == 0 || day > funs.len() {
-        bail!("day must be a number between 1 and {}", funs.len());
+                let report = run_report(day, source, expected);
+                if sender.send((index, report)).is_err() {
+                    break;
+                }
+            });
+        }
+
+        drop(sender);
+
+        let mut pending = HashMap::default();
+        let mut current = 0;
+
+        for (index, report) in receiver {
+            pending.insert(index, report);
+
+            while let Some(report) = pending.remove(&current) {
+                emit(report);
+                current += 1;
+            }
+        }
+    });
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut args = args.peekable();
+    let mut command = Command::Run;
+    let mut format = Format::Text;
+    let mut source = Source::Default;
+    let mut jobs = 1;
+    let mut selection = None;
+
+    if args.next_if(|arg| arg == "bench").is_some() {
+        command = Command::Bench(default());
     }
 
-    let mut input_file = String::new();
+    while let Some(arg) = args.next() {
+        let (name, mut value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
 
-    for &prefix in &[".", "..", "inputs", "../inputs/"] {
-        input_file = format!("{}/day{:02}", prefix, day);
+        if !name.starts_with("--") {
+            if selection.replace(arg).is_some() {
+                bail!("days can only be given once");
+            }
 
-        if Path::new(&input_file).exists() {
-            break;
+            continue;
+        }
+
+        let mut value = || {
+            value
+                .take()
+                .or_else(|| args.next())
+                .with_context(|| format!("missing value for {}", name))
+        };
+
+        match (name, &mut command) {
+            ("--format", _) => format = value()?.parse()?,
+            ("--input", _) => match value()?.as_str() {
+                "-" => source = Source::Stdin,
+                path => source = Source::Path(path.to_string()),
+            },
+            ("--example", _) => source = Source::Example,
+            ("--jobs", Command::Run) => jobs = value()?.parse()?,
+            ("--runs", Command::Bench(s)) => s.runs = value()?.parse()?,
+            ("--warmup", Command::Bench(s)) => s.warmup = value()?.parse()?,
+            ("--save", Command::Bench(s)) => s.save = Some(value()?),
+            ("--compare", Command::Bench(s)) => s.compare = Some(value()?),
+            _ => bail!("unknown option {:?}", name),
         }
     }
 
-    let content =
-        read_to_string(&input_file).with_context(|| format!("failed to open: {}", input_file))?;
-    let lines = content.trim_end().split('\n').collect::<Vec<_>>();
+    let days = parse_selection(&selection.unwrap_or_default())?;
+
+    if matches!(source, Source::Path(_) | Source::Stdin) && days.len() != 1 {
+        bail!("--input can only be used with a single day");
+    }
+
+    if jobs == 0 {
+        bail!("number of jobs must be at least one");
+    }
+
+    Ok(Options {
+        command,
+        days,
+        format,
+        source,
+        jobs,
+    })
+}
 
-    (funs[day - 1])(&lines)
+fn run_bench(days: &[usize], source: &Source, settings: &bench::Settings) -> Result {
+    let baseline = match &settings.compare {
+        Some(path) => bench::load(path)?,
+        None => vec![],
+    };
+
+    let mut measurements = vec![];
+    let mut failed = 0;
+
+    for &day in days {
+        let input = match input::load(day, source) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("day {:02} failed: {:#}", day, e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let result = catch_quietly(|| bench::measure(settings, day, || time_phases(day, &input)));
+
+        match result {
+            Ok(Ok(m)) => measurements.extend(m),
+            Ok(Err(e)) => {
+                println!("day {:02} failed: {:#}", day, e);
+                failed += 1;
+            }
+            Err(payload) => {
+                println!("day {:02} panicked: {}", day, panic_message(&*payload));
+                failed += 1;
+            }
+        }
+    }
+
+    bench::print_measurements(&measurements, &baseline);
+
+    if let Some(path) = &settings.save {
+        bench::save(path, &measurements)?;
+    }
+
+    if failed > 0 {
+        bail!("{} of {} days failed", failed, days.len());
+    }
+
+    Ok(())
+}
+
+fn main() -> Result {
+    let mut args = env::args();
+    let binary = args.next().unwrap_or_default();
+
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(e) => bail!("usage: {} {}\n{}", binary, USAGE, e),
+    };
+
+    install_panic_hook();
+
+    if let Command::Bench(settings) = &options.command {
+        return run_bench(&options.days, &options.source, settings);
+    }
+
+    let expected = read_expected()?;
+    let is_text = options.format == Format::Text;
+    let mut reports = vec![];
+
+    run_reports(
+        &options.days,
+        &options.source,
+        &expected,
+        options.jobs,
+        |report| {
+            if is_text {
+                if options.days.len() > 1 {
+                    println!("day {:02}:", report.day);
+                }
+
+                output::print_report(&report);
+            }
+
+            reports.push(report);
+        },
+    );
+
+    match options.format {
+        Format::Text if reports.len() > 1 => output::print_summary(&reports),
+        Format::Text => {}
+        Format::Json => output::print_json(&reports),
+        Format::Csv => output::print_csv(&reports),
+    }
+
+    let failed = reports.iter().filter(|r| r.status.is_err()).count();
+    let mismatches = reports
+        .iter()
+        .flat_map(|r| r.verdicts)
+        .filter(|&v| v == Verdict::Fail)
+        .count();
+
+    if failed > 0 || mismatches > 0 {
+        bail!(
+            "{} of {} days failed, {} answers did not match",
+            failed,
+            reports.len(),
+            mismatches
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_quietly() {
+        let result = catch_quietly(|| -> i32 { panic!("boom") });
+        assert_eq!(panic_message(&*result.unwrap_err()), "boom");
+        assert!(!QUIET.get());
+
+        assert_eq!(catch_quietly(|| 42).unwrap(), 42);
+        assert!(!QUIET.get());
+    }
+
+    #[test]
+    fn test_args() {
+        let args = |s: &str| parse_args(s.split_whitespace().map(String::from));
+
+        let options = args("--format json 3-4").unwrap();
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.days, [3, 4]);
+
+        let options = args("5 --format=csv").unwrap();
+        assert_eq!(options.format, Format::Csv);
+        assert_eq!(options.days, [5]);
+
+        assert_eq!(args("1").unwrap().format, Format::Text);
+        assert!(args("--format xml 1").is_err());
+        assert!(args("--format").is_err());
+        assert!(args("--verbose 1").is_err());
+        assert!(args("1 2").is_err());
+
+        let options = args("bench --runs 5 --save=out.txt 15").unwrap();
+        let settings = bench::Settings {
+            runs: 5,
+            save: Some("out.txt".to_string()),
+            ..default()
+        };
+        assert_eq!(options.command, Command::Bench(settings));
+        assert_eq!(options.days, [15]);
+
+        assert_eq!(args("1").unwrap().command, Command::Run);
+        assert_eq!(args("1").unwrap().source, Source::Default);
+        assert_eq!(args("--input - 1").unwrap().source, Source::Stdin);
+        assert_eq!(args("--example all").unwrap().source, Source::Example);
+
+        let options = args("--input=foo.txt 4").unwrap();
+        assert_eq!(options.source, Source::Path("foo.txt".to_string()));
+        assert!(args("--input foo.txt 4-5").is_err());
+
+        assert_eq!(args("1").unwrap().jobs, 1);
+        assert_eq!(args("--jobs 4 all").unwrap().jobs, 4);
+        assert!(args("--jobs 0 all").is_err());
+        assert!(args("bench --jobs 4 all").is_err());
+
+        assert!(args("--runs 5 1").is_err());
+        assert!(args("bench --runs x 1").is_err());
+    }
+
+    #[test]
+    fn test_selection() {
+        assert_eq!(parse_selection("7").unwrap(), [7]);
+        assert_eq!(parse_selection("3-5").unwrap(), [3, 4, 5]);
+        assert_eq!(parse_selection("1,5,17").unwrap(), [1, 5, 17]);
+        assert_eq!(parse_selection("1-2,24-25").unwrap(), [1, 2, 24, 25]);
+        assert_eq!(parse_selection("all").unwrap().len(), 25);
+
+        assert!(parse_selection("").is_err());
+        assert!(parse_selection("0").is_err());
+        assert!(parse_selection("26").is_err());
+        assert!(parse_selection("9-3").is_err());
+    }
 }
@@ -0,0 +1,237 @@
+use crate::Report;
+use advent2022::common::*;
+use std::fmt::Write as _;
+use std::iter::zip;
+use std::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => bail!("unknown format {:?}, expected json, csv or text", s),
+        }
+    }
+}
+
+const PARTS: [&str; 2] = ["A", "B"];
+
+fn answers(solution: &Solution) -> [&Answer; 2] {
+    [&solution.part_a, &solution.part_b]
+}
+
+pub(crate) fn print_report(report: &Report) {
+    let solution = match &report.status {
+        Ok(solution) => solution,
+        Err(msg) => {
+            println!("{}", msg);
+            return;
+        }
+    };
+
+    for ((name, answer), verdict) in zip(zip(PARTS, answers(solution)), report.verdicts) {
+        match answer {
+            Answer::Image(img) => println!("part {}: [{}]\n{}", name, verdict, img),
+            other => println!("part {}: {} [{}]", name, other, verdict),
+        }
+    }
+}
+
+fn summarize(answer: &Answer) -> String {
+    match answer {
+        Answer::Image(img) => {
+            let width = img.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+            format!("<{}x{} image>", width, img.lines().count())
+        }
+        other => other.to_string(),
+    }
+}
+
+pub(crate) fn print_summary(reports: &[Report]) {
+    let rows = reports
+        .iter()
+        .map(|report| match &report.status {
+            Ok(s) => {
+                let [a, b] = report.verdicts;
+                let status = format!("{} {}", a, b);
+                (report, summarize(&s.part_a), summarize(&s.part_b), status)
+            }
+            Err(msg) => (report, String::new(), String::new(), msg.clone()),
+        })
+        .collect_vec();
+
+    let width_a = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(6);
+    let width_b = rows.iter().map(|r| r.2.len()).max().unwrap_or(0).max(6);
+
+    println!();
+    println!(
+        "{:>3} | {:<width_a$} | {:<width_b$} | {:>12} | status",
+        "day", "part A", "part B", "time"
+    );
+    println!(
+        "{:->3}-+-{:-<width_a$}-+-{:-<width_b$}-+-{:->12}-+-{:-<6}",
+        "", "", "", "", ""
+    );
+
+    for (report, part_a, part_b, status) in rows {
+        println!(
+            "{:>3} | {:<width_a$} | {:<width_b$} | {:>9.3} ms | {}",
+            report.day,
+            part_a,
+            part_b,
+            report.duration.as_secs_f64() * 1000.0,
+            status
+        );
+    }
+}
+
+struct Record<'a> {
+    day: usize,
+    part: &'static str,
+    answer: Option<&'a Answer>,
+    parse_ns: u128,
+    duration_ns: u128,
+    input: &'a str,
+    input_hash: Option<String>,
+    status: String,
+}
+
+fn records(reports: &[Report]) -> Vec<Record<'_>> {
+    let mut output = vec![];
+
+    for report in reports {
+        let part_durations = [report.timings.part_a, report.timings.part_b];
+
+        for (index, part) in enumerate(PARTS) {
+            let (answer, status) = match &report.status {
+                Ok(solution) => (
+                    Some(answers(solution)[index]),
+                    report.verdicts[index].to_string(),
+                ),
+                Err(msg) => (None, msg.clone()),
+            };
+
+            output.push(Record {
+                day: report.day,
+                part,
+                answer,
+                parse_ns: report.timings.parse.as_nanos(),
+                duration_ns: part_durations[index].as_nanos(),
+                input: &report.input_path,
+                input_hash: report.input_hash.map(|h| format!("{:016x}", h)),
+                status,
+            });
+        }
+    }
+
+    output
+}
+
+fn answer_value(answer: &Answer) -> Option<String> {
+    match answer {
+        Answer::None => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut output = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(output, "\\u{:04x}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+fn json_value(value: Option<String>) -> String {
+    value.map_or_else(|| "null".to_string(), |s| json_string(&s))
+}
+
+pub(crate) fn print_json(reports: &[Report]) {
+    let records = records(reports);
+
+    println!("[");
+    for (index, r) in enumerate(&records) {
+        let answer = match r.answer {
+            Some(Answer::Int(v)) => v.to_string(),
+            Some(answer) => json_value(answer_value(answer)),
+            None => json_value(None),
+        };
+
+        let separator = if index + 1 < records.len() { "," } else { "" };
+
+        println!(
+            "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"parse_ns\": {}, \"duration_ns\": {}, \"input\": {}, \"input_hash\": {}, \"status\": {}}}{}",
+            r.day,
+            json_string(r.part),
+            answer,
+            r.parse_ns,
+            r.duration_ns,
+            json_string(r.input),
+            json_value(r.input_hash.clone()),
+            json_string(&r.status),
+            separator,
+        );
+    }
+    println!("]");
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains(['"', ',', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub(crate) fn print_csv(reports: &[Report]) {
+    println!("day,part,answer,parse_ns,duration_ns,input,input_hash,status");
+
+    for r in records(reports) {
+        println!(
+            "{},{},{},{},{},{},{},{}",
+            r.day,
+            r.part,
+            csv_field(&r.answer.and_then(answer_value).unwrap_or_default()),
+            r.parse_ns,
+            r.duration_ns,
+            csv_field(r.input),
+            r.input_hash.unwrap_or_default(),
+            csv_field(&r.status),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(json_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+        assert_eq!(json_string("\u{1}"), r#""\u0001""#);
+        assert_eq!(csv_field("abc"), "abc");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}